@@ -0,0 +1,31 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Validate a variable file against a schema exported with
+`tuna::schema::export`:
+
+```text
+cargo run --example validate -- schema.json variables.toml
+```
+*/
+
+fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let (schema, path) = match (args.next(), args.next()) {
+        (Some(schema), Some(path)) => (schema, path),
+        _ => anyhow::bail!("usage: validate <schema.json> <variables.toml>"),
+    };
+
+    let errors = tuna_file::validate_file(&path, &schema)?;
+    for error in &errors {
+        eprintln!("{}: {}", path, error);
+    }
+
+    if !errors.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
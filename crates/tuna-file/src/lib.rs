@@ -11,9 +11,13 @@ use std::{
     time::Duration,
 };
 
+mod schema;
+
+pub use schema::{validate, validate_file};
+
 use notify::{watcher, RecursiveMode, Watcher};
 use toml::Value;
-use tuna::{Boolean, Float32, Float64, Int32, Int64};
+use tuna::{Boolean, ChangeSource, Float32, Float64, Int32, Int64, Kind, SetError};
 
 pub struct FileWatcher {
     shutdown: Arc<AtomicBool>,
@@ -36,44 +40,64 @@ impl Drop for FileWatcher {
 
 type TomlContents = HashMap<String, HashMap<String, Value>>;
 
-fn apply_state(state: TomlContents) {
+fn apply_state(state: TomlContents, source: &ChangeSource) {
     for (category, kvs) in state {
         for (name, value) in kvs {
-            let success = match value {
-                Value::String(v) => {
-                    log::warn!(
-                        "unsupported string value: `{}/{} = {}`",
-                        &category,
-                        &name,
-                        v,
-                    );
-                    false
-                }
-                Value::Datetime(v) => {
-                    log::warn!(
-                        "unsupported string value: `{}/{} = {}`",
-                        &category,
-                        &name,
-                        v,
-                    );
-                    false
-                }
-                Value::Integer(v) => {
-                    tuna::set::<Int64>(&category, &name, v)
-                        || tuna::set::<Int32>(&category, &name, v as i32)
+            let result = match value {
+                Value::String(_) => Err(SetError::Parse("strings are not supported".to_owned())),
+                Value::Datetime(_) => {
+                    Err(SetError::Parse("datetimes are not supported".to_owned()))
                 }
+                // Integers are valid for float variables too, e.g. `gamma = 2`
+                Value::Integer(v) => match tuna::describe(&category, &name).map(|d| d.kind) {
+                    Some(Kind::Int32) => match i32::try_from(v) {
+                        Ok(v) => tuna::stage_from::<Int32>(&category, &name, v, source.clone()),
+                        Err(_) => Err(SetError::Parse(format!("{} does not fit in an i32", v))),
+                    },
+                    Some(Kind::Float64) => {
+                        tuna::stage_from::<Float64>(&category, &name, v as f64, source.clone())
+                    }
+                    Some(Kind::Float32) => {
+                        tuna::stage_from::<Float32>(&category, &name, v as f32, source.clone())
+                    }
+                    _ => tuna::stage_from::<Int64>(&category, &name, v, source.clone()),
+                },
                 Value::Float(v) => {
-                    tuna::set::<Float64>(&category, &name, v)
-                        || tuna::set::<Float32>(&category, &name, v as f32)
+                    match tuna::stage_from::<Float64>(&category, &name, v, source.clone()) {
+                        Err(SetError::TypeMismatch) => {
+                            tuna::stage_from::<Float32>(&category, &name, v as f32, source.clone())
+                        }
+                        res => res,
+                    }
+                }
+                Value::Boolean(v) => {
+                    tuna::stage_from::<Boolean>(&category, &name, v, source.clone())
                 }
-                Value::Boolean(v) => tuna::set::<Boolean>(&category, &name, v),
 
-                Value::Array(_) => false,
-                Value::Table(_) => false,
+                Value::Array(_) => Err(SetError::Parse("arrays are not supported".to_owned())),
+                Value::Table(_) => Err(SetError::Parse(
+                    "nested tables are not supported".to_owned(),
+                )),
             };
 
-            if !success {
-                log::error!("unknown tuneable: `{}/{}`", category, name);
+            match result {
+                Ok(()) => {}
+                Err(SetError::Rejected(msg)) => {
+                    log::error!("rejected value for `{}/{}`: {}", category, name, msg)
+                }
+                Err(SetError::Constraint(msg)) => {
+                    log::error!("constraint violated by `{}/{}`: {}", category, name, msg)
+                }
+                Err(SetError::ReadOnly) => {
+                    log::error!("cannot set derived tuneable: `{}/{}`", category, name)
+                }
+                Err(SetError::TypeMismatch) => {
+                    log::error!("value has the wrong type for `{}/{}`", category, name)
+                }
+                Err(SetError::Parse(msg)) => {
+                    log::error!("invalid value for `{}/{}`: {}", category, name, msg)
+                }
+                Err(SetError::NotFound) => log::error!("unknown tuneable: `{}/{}`", category, name),
             }
         }
     }
@@ -82,7 +106,8 @@ fn apply_state(state: TomlContents) {
 pub fn open(path: PathBuf, period: Duration) -> anyhow::Result<FileWatcher> {
     let initial_contents = std::fs::read_to_string(&path)?;
     let initial_state = toml::from_str(&initial_contents)?;
-    apply_state(initial_state);
+    let source = ChangeSource::File(path.display().to_string());
+    apply_state(initial_state, &source);
 
     let should_exit = Arc::new(AtomicBool::new(false));
     let shutdown = should_exit.clone();
@@ -111,7 +136,7 @@ pub fn open(path: PathBuf, period: Duration) -> anyhow::Result<FileWatcher> {
                     }
                 };
 
-                apply_state(state);
+                apply_state(state, &source);
             }
             Err(e) if e != RecvTimeoutError::Timeout => println!("watch error: {:?}", e),
             _ => {}
@@ -0,0 +1,194 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Offline validation of variable files against a schema from
+`tuna::schema::export`. Supports the subset of JSON Schema that tuna emits:
+`type`, `properties`, `additionalProperties`, `minimum`, `maximum`, `enum` and
+`readOnly`.
+*/
+
+use std::path::Path;
+
+use serde_json::Value as Schema;
+use toml::Value;
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "number",
+        Value::Boolean(_) => "boolean",
+        Value::Datetime(_) => "datetime",
+        Value::Array(_) => "array",
+        Value::Table(_) => "object",
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(v) => Some(*v as f64),
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn matches_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_table(),
+        "number" => value.is_integer() || value.is_float(),
+        "integer" => value.is_integer(),
+        "boolean" => value.is_bool(),
+        "string" => value.is_str(),
+        "array" => value.is_array(),
+        _ => true,
+    }
+}
+
+fn equals(value: &Value, expected: &Schema) -> bool {
+    match (value, expected) {
+        (Value::Boolean(a), Schema::Bool(b)) => a == b,
+        (Value::String(a), Schema::String(b)) => a == b,
+        (value, Schema::Number(b)) => as_f64(value) == b.as_f64(),
+        _ => false,
+    }
+}
+
+fn check(path: &str, value: &Value, schema: &Schema, errors: &mut Vec<String>) {
+    if schema.get("readOnly").and_then(Schema::as_bool) == Some(true) {
+        errors.push(format!("{}: is read-only", path));
+        return;
+    }
+
+    if let Some(ty) = schema.get("type").and_then(Schema::as_str) {
+        if !matches_type(value, ty) {
+            errors.push(format!(
+                "{}: expected {}, found {}",
+                path,
+                ty,
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(number) = as_f64(value) {
+        if let Some(min) = schema.get("minimum").and_then(Schema::as_f64) {
+            if number < min {
+                errors.push(format!("{}: {} is less than {}", path, number, min));
+            }
+        }
+
+        if let Some(max) = schema.get("maximum").and_then(Schema::as_f64) {
+            if number > max {
+                errors.push(format!("{}: {} is greater than {}", path, number, max));
+            }
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Schema::as_array) {
+        if !options.iter().any(|option| equals(value, option)) {
+            errors.push(format!("{}: {} is not an allowed value", path, value));
+        }
+    }
+
+    if let Value::Table(table) = value {
+        let properties = schema.get("properties").and_then(Schema::as_object);
+        let closed = schema.get("additionalProperties").and_then(Schema::as_bool) == Some(false);
+
+        for (key, value) in table {
+            let path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+
+            match properties.and_then(|p| p.get(key)) {
+                Some(schema) => check(&path, value, schema, errors),
+                None if closed => errors.push(format!("{}: unknown tuneable", path)),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Validate the contents of a variable file against a schema, returning a
+/// message for every problem found. Errors if either can't be parsed.
+pub fn validate(contents: &str, schema: &str) -> anyhow::Result<Vec<String>> {
+    let value: Value = toml::from_str(contents)?;
+    let schema: Schema = serde_json::from_str(schema)?;
+
+    let mut errors = vec![];
+    check("", &value, &schema, &mut errors);
+    Ok(errors)
+}
+
+/// Validate a variable file against a schema file, see [`validate`]
+pub fn validate_file(
+    path: impl AsRef<Path>,
+    schema: impl AsRef<Path>,
+) -> anyhow::Result<Vec<String>> {
+    validate(
+        &std::fs::read_to_string(path)?,
+        &std::fs::read_to_string(schema)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+
+    const SCHEMA: &str = r#"{
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "properties": {
+            "render": {
+                "type": "object",
+                "properties": {
+                    "gamma": { "type": "number", "minimum": 1, "maximum": 3 },
+                    "samples": { "type": "integer", "enum": [1, 2, 4, 8] },
+                    "shadows": { "type": "boolean" },
+                    "lod": { "type": "number", "readOnly": true }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false
+    }"#;
+
+    #[test]
+    fn valid() {
+        let contents = "[render]\ngamma = 2\nsamples = 4\nshadows = true\n";
+        assert!(validate(contents, SCHEMA).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        let contents = r#"
+            [render]
+            gamma = 3.5
+            samples = 3
+            shadows = "yes"
+            lod = 1.0
+            fog = 1
+
+            [audio]
+            volume = 1
+        "#;
+
+        let mut errors = validate(contents, SCHEMA).unwrap();
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "audio: unknown tuneable",
+                "render.fog: unknown tuneable",
+                "render.gamma: 3.5 is greater than 3",
+                "render.lod: is read-only",
+                "render.samples: 3 is not an allowed value",
+                "render.shadows: expected boolean, found string",
+            ]
+        );
+    }
+}
@@ -0,0 +1,259 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+`#[derive(Tuneable)]` for plain structs, registering every field as a variable
+in one category.
+*/
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Lit, Meta, NestedMeta};
+
+#[derive(Default)]
+struct FieldAttrs {
+    min: Option<Lit>,
+    max: Option<Lit>,
+    soft_min: Option<Lit>,
+    soft_max: Option<Lit>,
+    default: Option<Lit>,
+    description: Option<String>,
+}
+
+type Pairs = Vec<(syn::Path, Lit)>;
+
+/// Collect the `#[tuna(...)]` key-value pairs and doc comments of an item
+fn parse_attrs(attrs: &[syn::Attribute]) -> syn::Result<(Pairs, Vec<String>)> {
+    let mut pairs = vec![];
+    let mut docs = vec![];
+
+    for attr in attrs {
+        match attr.parse_meta()? {
+            Meta::NameValue(v) if v.path.is_ident("doc") => {
+                if let Lit::Str(s) = v.lit {
+                    docs.push(s.value().trim().to_owned());
+                }
+            }
+            Meta::List(list) if list.path.is_ident("tuna") => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::NameValue(v)) => pairs.push((v.path, v.lit)),
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "expected `key = value` in #[tuna(...)]",
+                            ))
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((pairs, docs))
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    let (pairs, _) = parse_attrs(&input.attrs)?;
+    let mut category = ident.to_string();
+    for (path, lit) in pairs {
+        match (path.get_ident().map(|i| i.to_string()).as_deref(), lit) {
+            (Some("category"), Lit::Str(s)) => category = s.value(),
+            (_, lit) => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "expected `category = \"...\"` on the struct",
+                ))
+            }
+        }
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "Tuneable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "Tuneable can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut registers = vec![];
+    let mut keys = vec![];
+    let mut reads = vec![];
+    let mut writes = vec![];
+    let mut needs_default = false;
+
+    for field in fields {
+        let name = field.ident.as_ref().expect("fields are named");
+        let key = name.to_string();
+
+        let (variable_type, numeric) = match &field.ty {
+            syn::Type::Path(p) if p.path.is_ident("f32") => ("Float32", true),
+            syn::Type::Path(p) if p.path.is_ident("f64") => ("Float64", true),
+            syn::Type::Path(p) if p.path.is_ident("i32") => ("Int32", true),
+            syn::Type::Path(p) if p.path.is_ident("i64") => ("Int64", true),
+            syn::Type::Path(p) if p.path.is_ident("bool") => ("Boolean", false),
+            ty => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "unsupported field type, expected f32, f64, i32, i64 or bool",
+                ))
+            }
+        };
+        let ty = format_ident!("{}", variable_type);
+
+        let (pairs, docs) = parse_attrs(&field.attrs)?;
+        let mut attrs = FieldAttrs::default();
+        for (path, lit) in pairs {
+            match path.get_ident().map(|i| i.to_string()).as_deref() {
+                Some("min") if numeric => attrs.min = Some(lit),
+                Some("max") if numeric => attrs.max = Some(lit),
+                Some("soft_min") if numeric => attrs.soft_min = Some(lit),
+                Some("soft_max") if numeric => attrs.soft_max = Some(lit),
+                Some("default") => attrs.default = Some(lit),
+                Some("description") => match lit {
+                    Lit::Str(s) => attrs.description = Some(s.value()),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                },
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "expected one of `min`, `max`, `soft_min`, `soft_max`, `default` or `description`",
+                    ))
+                }
+            }
+        }
+
+        let description = attrs
+            .description
+            .or_else(|| (!docs.is_empty()).then(|| docs.join(" ")))
+            .map_or(quote! {}, |d| quote! { .with_description(#d) });
+
+        // Fields without a `default` attribute take it from `Default`, which
+        // reads only evaluate on a lookup miss
+        let (default, read_default) = match &attrs.default {
+            Some(lit) => (quote! { #lit }, quote! { unwrap_or(#lit) }),
+            None => {
+                needs_default = true;
+                (
+                    quote! { defaults.#name },
+                    quote! {
+                        unwrap_or_else(|| {
+                            defaults
+                                .get_or_insert_with(<Self as ::core::default::Default>::default)
+                                .#name
+                        })
+                    },
+                )
+            }
+        };
+
+        let definition = if numeric {
+            let min = attrs.min.map_or(quote! { None }, |v| quote! { Some(#v) });
+            let max = attrs.max.map_or(quote! { None }, |v| quote! { Some(#v) });
+            let soft = if attrs.soft_min.is_some() || attrs.soft_max.is_some() {
+                let soft_min = attrs
+                    .soft_min
+                    .map_or(quote! { None }, |v| quote! { Some(#v) });
+                let soft_max = attrs
+                    .soft_max
+                    .map_or(quote! { None }, |v| quote! { Some(#v) });
+                quote! { .with_soft_range(#soft_min, #soft_max) }
+            } else {
+                quote! {}
+            };
+            quote! { tuna::#ty::new(#category, #key, #default, #min, #max)#soft }
+        } else {
+            quote! { tuna::#ty::new(#category, #key, #default) }
+        };
+
+        registers.push(quote! { #definition #description.register(); });
+        keys.push(key.clone());
+        reads.push(quote! {
+            #name: tuna::__private::get_locked::<tuna::#ty>(state, #category, #key).#read_default
+        });
+        writes.push(quote! { tx.set::<tuna::#ty>(#category, #key, self.#name); });
+    }
+
+    if fields.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "Tuneable needs at least one field",
+        ));
+    }
+
+    let (defaults, defaults_arg) = if needs_default {
+        (
+            quote! { let defaults = <Self as ::core::default::Default>::default(); },
+            quote! { defaults },
+        )
+    } else {
+        (quote! {}, quote! { _ })
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Generic structs have no single `register` to submit
+    let submit = if input.generics.params.is_empty() {
+        quote! { tuna::__submit!(#ident::register); }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The category all fields are registered under
+            pub const CATEGORY: &'static str = #category;
+
+            /// Register every field with tuna. This is not required, but it'll
+            /// reduce risk of stuttering when variables get registered.
+            pub fn register() {
+                #defaults
+                #(#registers)*
+            }
+
+            /// Read all fields at once from a consistent state. Registers the
+            /// fields if any of them is missing.
+            pub fn read() -> Self {
+                let mut defaults: ::core::option::Option<Self> = None;
+                let read = |state: &tuna::TunaState, #defaults_arg: &mut ::core::option::Option<Self>| Self {
+                    #(#reads,)*
+                };
+
+                {
+                    let state = tuna::TUNA_STATE.read();
+                    if state.get(#category).is_some_and(|group| #(group.contains_key(#keys))&&*) {
+                        return read(&state, &mut defaults);
+                    }
+                }
+
+                Self::register();
+                let state = tuna::TUNA_STATE.read();
+                read(&state, &mut defaults)
+            }
+
+            /// Write all fields at once; either every field is written, or
+            /// none are.
+            pub fn write(&self) -> ::core::result::Result<(), tuna::SetError> {
+                tuna::transaction(|tx| {
+                    #(#writes)*
+                })
+            }
+        }
+
+        #submit
+    })
+}
@@ -1,3 +1,5 @@
+mod derive;
+
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
@@ -7,7 +9,7 @@ use syn::{
     parse_macro_input,
     punctuated::Punctuated,
     token::{Brace, Const},
-    Attribute, Expr, Ident, Meta, Token, Visibility,
+    Attribute, DeriveInput, Expr, Ident, Lit, Meta, Token, Visibility,
 };
 
 struct FieldLike {
@@ -50,37 +52,76 @@ impl quote::ToTokens for FieldLike {
             #defaults
         };
 
+        // Doc comments are kept, and become the description of the variable
+        let docs = attrs
+            .iter()
+            .filter_map(|v| match v {
+                Meta::NameValue(v) if v.path.is_ident("doc") => match &v.lit {
+                    Lit::Str(s) => Some(s.value()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let description = if docs.is_empty() {
+            quote! {}
+        } else {
+            let text = docs.iter().map(|d| d.trim()).collect::<Vec<_>>().join(" ");
+            quote! { .with_description(#text) }
+        };
+
+        // `#[wrap]` makes values wrap around the range instead of clamping
+        let wrap = attrs
+            .iter()
+            .any(|v| matches!(v, Meta::Path(p) if p.is_ident("wrap")));
+
         let out = if numeric {
-            let min = attrs
-                .iter()
-                .find(|v| v.path().is_ident("min"))
-                .map(|v| match v {
-                    Meta::NameValue(v) => v,
-                    _ => panic!("accepts only kv pairs"),
-                })
-                .map_or(quote! {None}, |v| {
-                    let lit = &v.lit;
-                    quote! { Some(#lit) }
-                });
-
-            let max = attrs
-                .iter()
-                .find(|v| v.path().is_ident("max"))
-                .map(|v| match v {
-                    Meta::NameValue(v) => v,
-                    _ => panic!("accepts only kv pairs"),
-                })
-                .map_or(quote! {None}, |v| {
-                    let lit = &v.lit;
-                    quote! { Some(#lit) }
-                });
+            let bound = |name: &str| {
+                attrs
+                    .iter()
+                    .find(|v| v.path().is_ident(name))
+                    .map(|v| match v {
+                        Meta::NameValue(v) => v,
+                        _ => panic!("accepts only kv pairs"),
+                    })
+                    .map(|v| {
+                        let lit = &v.lit;
+                        quote! { Some(#lit) }
+                    })
+            };
+
+            let min = bound("min").unwrap_or(quote! {None});
+            let max = bound("max").unwrap_or(quote! {None});
+
+            // `#[soft_min]` and `#[soft_max]` only suggest a range to tools
+            let soft = match (bound("soft_min"), bound("soft_max")) {
+                (None, None) => quote! {},
+                (soft_min, soft_max) => {
+                    let soft_min = soft_min.unwrap_or(quote! {None});
+                    let soft_max = soft_max.unwrap_or(quote! {None});
+                    quote! { .with_soft_range(#soft_min, #soft_max) }
+                }
+            };
+
+            let wrap = if wrap {
+                quote! { .wrapping() }
+            } else {
+                quote! {}
+            };
 
             quote! {
-                #vis #constness #ident #colon_token tuna::#ty #equals tuna::#ty::new(NAME, stringify!(#ident), #default, #min, #max)
+                #(#[doc = #docs])*
+                #vis #constness #ident #colon_token tuna::#ty #equals tuna::#ty::new(NAME, stringify!(#ident), #default, #min, #max)#soft #wrap #description
             }
         } else {
+            if wrap {
+                panic!("#[wrap] only applies to numeric variables");
+            }
+
             quote! {
-                #vis #constness #ident #colon_token tuna::#ty #equals tuna::#ty::new(NAME, stringify!(#ident), #default)
+                #(#[doc = #docs])*
+                #vis #constness #ident #colon_token tuna::#ty #equals tuna::#ty::new(NAME, stringify!(#ident), #default)#description
             }
         };
 
@@ -164,8 +205,19 @@ pub fn tuna(_attr: TokenStream, item: TokenStream) -> TokenStream {
             pub fn register() {
                 #(#fns)*
             }
+
+            tuna::__submit!(register);
         }
     );
 
     res.into()
 }
+
+#[proc_macro_derive(Tuneable, attributes(tuna))]
+pub fn derive_tuneable(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    derive::expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
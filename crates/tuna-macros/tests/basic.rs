@@ -55,3 +55,205 @@ fn test_derive_bool() {
         const FOO: bool = false;
     }
 }
+
+#[test]
+fn test_derive_description() {
+    #[tuna_macros::tuna]
+    mod described {
+        /// How strongly things fall,
+        /// in m/s²
+        #[min = -20.0]
+        pub const GRAVITY: f32 = -9.81;
+    }
+
+    described::register();
+    let descriptor = tuna::describe("described", "GRAVITY").unwrap();
+    assert_eq!(
+        descriptor.description.as_deref(),
+        Some("How strongly things fall, in m/s²")
+    );
+}
+
+#[test]
+fn test_derive_wrap() {
+    #[tuna_macros::tuna]
+    mod wrapped {
+        #[min = 0.0]
+        #[max = 360.0]
+        #[wrap]
+        pub const YAW: f32 = 0.0;
+
+        #[min = 0.0]
+        #[max = 360.0]
+        pub const PITCH: f32 = 0.0;
+    }
+
+    wrapped::register();
+    assert_eq!(wrapped::YAW.mode(), tuna::RangeMode::Wrap);
+    assert_eq!(wrapped::PITCH.mode(), tuna::RangeMode::Clamp);
+
+    wrapped::YAW.write(370.0);
+    assert_eq!(wrapped::YAW.read(), 10.0);
+    wrapped::PITCH.write(370.0);
+    assert_eq!(wrapped::PITCH.read(), 360.0);
+}
+
+#[test]
+fn test_derive_soft_range() {
+    #[tuna_macros::tuna]
+    mod soft {
+        #[min = 0.0]
+        #[soft_min = 0.5]
+        #[soft_max = 2.0]
+        pub const SCALE: f32 = 1.0;
+    }
+
+    soft::register();
+    let descriptor = tuna::describe("soft", "SCALE").unwrap();
+    assert_eq!(descriptor.min, Some(0.0));
+    assert_eq!(descriptor.max, None);
+    assert_eq!(descriptor.soft_min, Some(0.5));
+    assert_eq!(descriptor.soft_max, Some(2.0));
+}
+
+#[test]
+fn test_register_all() {
+    #[tuna_macros::tuna]
+    mod automatic {
+        pub const NEVER_REGISTERED: bool = true;
+    }
+
+    #[derive(tuna_macros::Tuneable, Default)]
+    #[tuna(category = "automatic_struct")]
+    struct Automatic {
+        count: i32,
+    }
+
+    assert!(tuna::register_all() >= 2);
+    assert!(tuna::is_registered("automatic", "NEVER_REGISTERED"));
+    assert!(tuna::is_registered("automatic_struct", "count"));
+}
+
+#[test]
+fn test_derive_struct() {
+    #[derive(tuna_macros::Tuneable, Clone, Debug, PartialEq)]
+    #[tuna(category = "camera_params")]
+    struct CameraParams {
+        /// Vertical field of view
+        #[tuna(min = 10.0, max = 170.0, default = 60.0)]
+        fov: f32,
+        #[tuna(min = 0.0, soft_max = 1.0, description = "Near plane")]
+        near: f32,
+        far: f32,
+        orthographic: bool,
+    }
+
+    impl Default for CameraParams {
+        fn default() -> Self {
+            Self {
+                fov: 45.0,
+                near: 0.1,
+                far: 1000.0,
+                orthographic: false,
+            }
+        }
+    }
+
+    let params = CameraParams::read();
+    assert_eq!(
+        params,
+        CameraParams {
+            fov: 60.0,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        tuna::describe("camera_params", "fov")
+            .unwrap()
+            .description
+            .as_deref(),
+        Some("Vertical field of view")
+    );
+    assert_eq!(
+        tuna::describe("camera_params", "near").unwrap().soft_max,
+        Some(1.0)
+    );
+
+    let wide = CameraParams {
+        fov: 200.0,
+        orthographic: true,
+        ..params
+    };
+    wide.write().unwrap();
+    assert_eq!(CameraParams::read(), CameraParams { fov: 170.0, ..wide });
+}
+
+#[test]
+fn test_derive_missing_field() {
+    #[derive(tuna_macros::Tuneable, Debug, PartialEq)]
+    #[tuna(category = "partial")]
+    struct Partial {
+        #[tuna(default = 1.0)]
+        first: f32,
+        #[tuna(default = 5)]
+        second: i32,
+    }
+
+    // Only the first field is registered by someone else
+    tuna::Float32::new("partial", "first", 1.0, None, None).register();
+    assert!(!tuna::is_registered("partial", "second"));
+
+    assert_eq!(
+        Partial::read(),
+        Partial {
+            first: 1.0,
+            second: 5
+        }
+    );
+    assert!(tuna::is_registered("partial", "second"));
+}
+
+#[test]
+fn test_derive_explicit_defaults() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // No `Default` needed when every field has a default
+    #[derive(tuna_macros::Tuneable, Debug, PartialEq)]
+    #[tuna(category = "explicit_defaults")]
+    struct Explicit {
+        #[tuna(default = 3)]
+        lives: i32,
+        #[tuna(default = true)]
+        music: bool,
+    }
+
+    assert_eq!(
+        Explicit::read(),
+        Explicit {
+            lives: 3,
+            music: true
+        }
+    );
+
+    static DEFAULTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(tuna_macros::Tuneable)]
+    #[tuna(category = "counted_defaults")]
+    struct Counted {
+        speed: f32,
+    }
+
+    impl Default for Counted {
+        fn default() -> Self {
+            DEFAULTS.fetch_add(1, Ordering::Relaxed);
+            Self { speed: 2.0 }
+        }
+    }
+
+    Counted::register();
+    let registered = DEFAULTS.load(Ordering::Relaxed);
+    for _ in 0..3 {
+        assert_eq!(Counted::read().speed, 2.0);
+    }
+    assert_eq!(DEFAULTS.load(Ordering::Relaxed), registered);
+}
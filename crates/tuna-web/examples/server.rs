@@ -28,6 +28,8 @@ const BOOL_VALUE1: Boolean = Boolean::new("bool", "name1", true);
 const BOOL_VALUE2: Boolean = Boolean::new("bool", "name2", true);
 const BOOL_VALUE3: Boolean = Boolean::new("bool", "name3", false);
 
+const DERIVED_VALUE1: Derived = Derived::new("derived", "name1", "float.name1 * int.name20");
+
 fn main() {
     FLOAT_VALUE1.register();
     FLOAT_VALUE2.register();
@@ -49,10 +51,17 @@ fn main() {
     BOOL_VALUE2.register();
     BOOL_VALUE3.register();
 
+    DERIVED_VALUE1.register();
+
     env_logger::init();
     let mut server = tuna_web::TunaServer::new(4450).unwrap();
 
+    let mut last = std::time::Instant::now();
     loop {
         server.loop_once();
+
+        let now = std::time::Instant::now();
+        tuna::tick((now - last).as_secs_f64());
+        last = now;
     }
 }
@@ -25,6 +25,10 @@ function tuneableToTag(tuneable) {
         return "float";
     }
 
+    if (tuneable.hasOwnProperty("Derived")) {
+        return "derived";
+    }
+
     throw "Unknown variable type: " + tuneable;
 }
 
@@ -49,9 +53,31 @@ function getInner(tuneable) {
         return tuneable["Float64"][0];
     }
 
+    if (tuneable.hasOwnProperty("Derived")) {
+        return tuneable["Derived"][0];
+    }
+
     throw "Unknown variable type: " + tuneable;
 }
 
+function isWrapping(tuneable) {
+    let mode = getInner(tuneable).mode;
+    return mode !== undefined && mode !== null && mode.hasOwnProperty("Wrap");
+}
+
+function orElse(value, fallback) {
+    return value == null ? fallback : value;
+}
+
+// Unbounded ends must not be set, or they become "undefined"
+function setBound(widget, attribute, value) {
+    if (value == null) {
+        widget.removeAttribute(attribute);
+    } else {
+        widget[attribute] = value;
+    }
+}
+
 function tuneableToValue(tuneable) {
     return getInner(tuneable).current;
 }
@@ -61,8 +87,15 @@ function tuneableToWidgetConfig(type, tuneable, widget) {
         case "float":
         case "int": {
             let inner = getInner(tuneable);
-            widget.min = inner.min;
-            widget.max = inner.max;
+
+            // The slider covers the suggested range, or the hard limits if
+            // there is none. Without either there's nothing to slide over.
+            let min = orElse(inner.soft_min, inner.min);
+            let max = orElse(inner.soft_max, inner.max);
+            setBound(widget, "min", min);
+            setBound(widget, "max", max);
+            widget.hidden = min == null || max == null;
+            widget.step = type == "int" ? 1 : "any";
             widget.default = inner.default;
             widget.value = inner.current;
             break;
@@ -70,6 +103,15 @@ function tuneableToWidgetConfig(type, tuneable, widget) {
 
         case "bool": {
             let inner = tuneable["Bool"];
+            break;
+        }
+
+        case "derived": {
+            let inner = getInner(tuneable);
+            widget.innerText = inner.error
+                ? `${inner.expression} = (${inner.error})`
+                : `${inner.expression} = ${inner.current}`;
+            break;
         }
     }
 }
@@ -92,10 +134,10 @@ class VarGroup {
 
     ////////////////////////////////////////////////////////////////////////////////
 
-    filter(needle) {
+    filter(visible) {
         let anyVisible = false;
         this.variables.forEach((value, key) => {
-            if (value.fullName.toLowerCase().includes(needle)) {
+            if (visible.has(value.fullName)) {
                 value.show();
                 anyVisible = true;
             } else {
@@ -148,11 +190,16 @@ class Var {
             case "float":
             case "int":
                 this.realWidget.value = this.value;
+                this.updateNumber();
                 break;
             case "bool":
                 this.realWidget.checked = this.value;
                 break;
         }
+
+        if (this.updateDial) {
+            this.updateDial();
+        }
     }
 
     ////////////////////////////////////////////////////////////////////////////////
@@ -179,7 +226,13 @@ class Var {
         let label = row.children[0];
         label.innerText = this.name;
 
-        let widget = document.createElement("input");
+        this.sourceWidget = document.createElement("small");
+        this.sourceWidget.className = "last-source";
+        label.appendChild(this.sourceWidget);
+
+        let widget = document.createElement(
+            this.type == "derived" ? "span" : "input"
+        );
         switch (this.type) {
             case "int": // int
                 widget.type = "range";
@@ -194,37 +247,163 @@ class Var {
                 widget.type = "checkbox";
                 widget.checked = this.value;
                 break;
+            case "derived":
+                widget.classList.add("derived");
+                tuneableToWidgetConfig(this.type, this.tuneable, widget);
+                break;
         }
 
         console.log(widget, this.type);
         widget.onchange = this.onchange.bind(this);
         row.children[1].appendChild(widget);
+
+        if (this.type == "int" || this.type == "float") {
+            // Typed values may go beyond the slider, up to the hard limits
+            this.numberWidget = document.createElement("input");
+            this.numberWidget.type = "number";
+            this.numberWidget.className = "number";
+            this.numberWidget.onchange = this.onchange.bind(this);
+            row.children[1].appendChild(this.numberWidget);
+
+            if (isWrapping(this.tuneable)) {
+                row.children[1].appendChild(this.createDial(widget));
+            }
+            row.children[1].appendChild(this.createAnimator());
+        }
+
         this.lock = row.children[2].children[0];
         this.realWidget = widget;
         this.topWidget = row;
         this.topWidget.classList.add("table-success");
 
+        if (this.numberWidget) {
+            this.updateNumber();
+        }
+
         return row;
     }
 
     ////////////////////////////////////////////////////////////////////////////////
 
+    updateNumber() {
+        let inner = getInner(this.tuneable);
+        let number = this.numberWidget;
+        setBound(number, "min", inner.min);
+        setBound(number, "max", inner.max);
+        number.step = this.type == "int" ? 1 : "any";
+        number.value = this.value;
+
+        let outside =
+            (inner.soft_min != null && this.value < inner.soft_min) ||
+            (inner.soft_max != null && this.value > inner.soft_max);
+        this.topWidget.children[1].classList.toggle("out-of-range", outside);
+        number.title = outside
+            ? `outside the recommended range ${orElse(inner.soft_min, "-∞")} to ${orElse(inner.soft_max, "∞")}`
+            : "";
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////
+
+    createDial(widget) {
+        let dial = document.createElement("span");
+        dial.className = "dial";
+        let needle = document.createElement("span");
+        needle.className = "dial-needle";
+        dial.appendChild(needle);
+
+        this.updateDial = () => {
+            let inner = getInner(this.tuneable);
+            let turns = (this.value - inner.min) / (inner.max - inner.min);
+            needle.style.transform = `rotate(${turns * 360}deg)`;
+        };
+        this.updateDial();
+
+        // Dragging around the dial goes past max back to min without a jump
+        let drag = (e) => {
+            let inner = getInner(this.tuneable);
+            let rect = dial.getBoundingClientRect();
+            let angle = Math.atan2(
+                e.clientX - (rect.left + rect.width / 2),
+                rect.top + rect.height / 2 - e.clientY
+            );
+            let turns = (angle / (2 * Math.PI) + 1) % 1;
+            widget.value = inner.min + turns * (inner.max - inner.min);
+            widget.dispatchEvent(new Event("change"));
+        };
+
+        dial.onpointerdown = (e) => {
+            dial.setPointerCapture(e.pointerId);
+            dial.onpointermove = drag;
+            drag(e);
+        };
+        dial.onpointerup = () => {
+            dial.onpointermove = null;
+        };
+
+        return dial;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////
+
+    createAnimator() {
+        var html = document.getElementById("animator-template");
+
+        let newNode = document.importNode(html.content, true);
+        let container = newNode.querySelector(".animator");
+        let kind = container.querySelector("select");
+        let parameter = container.querySelector("input");
+
+        let update = () => {
+            if (kind.value == "") {
+                window.tuna.stopAnimation(this.category, this.name);
+            } else {
+                window.tuna.animate(
+                    this.category,
+                    this.name,
+                    kind.value,
+                    parseFloat(parameter.value)
+                );
+            }
+        };
+
+        kind.onchange = update;
+        parameter.onchange = update;
+
+        return container;
+    }
+
+    ////////////////////////////////////////////////////////////////////////////////
+
     onchange(e) {
         switch (this.type) {
             case "int":
-                this.value = e.target.value;
-                getInner(this.tuneable).current = parseInt(this.value);
-                break;
-            case "float":
-                this.value = e.target.value;
-                getInner(this.tuneable).current = parseFloat(this.value);
+            case "float": {
+                let value =
+                    this.type == "int"
+                        ? parseInt(e.target.value)
+                        : parseFloat(e.target.value);
+                if (isNaN(value)) {
+                    return;
+                }
+
+                this.value = value;
+                getInner(this.tuneable).current = value;
+
+                // Keep the slider and the typed value in sync
+                this.realWidget.value = value;
+                this.updateNumber();
                 break;
+            }
             case "bool":
                 this.value = e.target.checked;
                 getInner(this.tuneable).current = this.value;
                 break;
         }
 
+        if (this.updateDial) {
+            this.updateDial();
+        }
+
         this.topWidget.classList.remove("table-success");
         this.topWidget.classList.add("table-warning");
         window.tuna.set(this.category, this.name, this.tuneable);
@@ -236,13 +415,46 @@ class Vars {
         this.groups = new Map();
         this.vars = new Map();
         document.getElementById("-searchbox").oninput = this.filter.bind(this);
+        document.getElementById("-stats").onclick = () => window.tuna.stats();
     }
 
     ////////////////////////////////////////////////////////////////////////////////
 
     filter(eve) {
-        let needle = eve.target.value.toLowerCase();
-        this.groups.forEach((group) => group.filter(needle));
+        window.tuna.search(eve.target.value);
+    }
+
+    showResults(results) {
+        let visible = new Set(results.map(([category, name]) => `${category}.${name}`));
+        this.groups.forEach((group) => group.filter(visible));
+    }
+
+    showStats(stats) {
+        let time = (seconds) =>
+            seconds == null ? "never" : new Date(seconds * 1000).toLocaleTimeString();
+
+        for (let entry of stats) {
+            let item = this.vars.get(`${entry.category}.${entry.name}`);
+            if (!item) {
+                continue;
+            }
+
+            let label = item.topWidget.children[0];
+            label.title =
+                `read ${entry.reads} times, last at ${time(entry.last_read)}\n` +
+                `written ${entry.writes} times, last at ${time(entry.last_write)}` +
+                (entry.last_writer == null ? "" : ` by ${entry.last_writer}`);
+            label.classList.toggle("never-read", entry.reads == 0);
+        }
+    }
+
+    showSources(sources) {
+        for (let [category, name, source] of sources) {
+            let item = this.vars.get(`${category}.${name}`);
+            if (item) {
+                item.sourceWidget.innerText = `last changed by ${source}`;
+            }
+        }
     }
 
     reset() {}
@@ -263,11 +475,20 @@ class Vars {
                 }
             }
         } else if (msg.hasOwnProperty("Delta")) {
-            const [category, name, tuneable] = msg["Delta"];
+            const [[category, name, tuneable]] = msg["Delta"];
             this.updateVariable(category, name, tuneable);
+        } else if (msg.hasOwnProperty("Stats")) {
+            this.showStats(msg["Stats"][0]);
+        } else if (msg.hasOwnProperty("Sources")) {
+            this.showSources(msg["Sources"][0]);
+        } else if (msg.hasOwnProperty("SearchResults")) {
+            this.showResults(msg["SearchResults"][0]);
         } else if (msg.hasOwnProperty("Ok")) {
             const [[category, name]] = msg["Ok"];
             this.ok(category, name);
+        } else if (msg.hasOwnProperty("Error")) {
+            const [[category, name, message]] = msg["Error"];
+            this.error(category, name, message);
         }
     }
 
@@ -309,5 +530,59 @@ class Vars {
 
         item.topWidget.classList.add("table-success");
         item.topWidget.classList.remove("table-warning");
+        item.topWidget.classList.remove("table-danger");
+        item.topWidget.title = "";
+    }
+
+    error(category, name, message) {
+        let fullName = `${category}.${name}`;
+        let item = this.vars.get(fullName);
+        if (item === undefined) {
+            // Errors about A/B slots name no variable
+            console.error(`${name}: ${message}`);
+            return;
+        }
+
+        item.topWidget.classList.remove("table-success");
+        item.topWidget.classList.remove("table-warning");
+        item.topWidget.classList.add("table-danger");
+        item.topWidget.title = message;
+        console.error(`${fullName}: ${message}`);
+    }
+}
+
+class AbPanel {
+    constructor() {
+        document.getElementById("-ab-capture").onclick = () => {
+            window.tuna.capture(
+                document.getElementById("-ab-slot").value,
+                document.getElementById("-ab-pattern").value
+            );
+        };
+        document.getElementById("-ab-toggle").onclick = () => window.tuna.toggle();
+    }
+
+    addData(msg) {
+        if (msg.hasOwnProperty("AbSlots")) {
+            const [[slots, active]] = msg["AbSlots"];
+            this.showSlots(slots, active);
+
+            // Activating a slot changes variables behind our back
+            window.tuna.listAll();
+        }
+    }
+
+    showSlots(slots, active) {
+        let container = document.getElementById("-ab-slots");
+        container.innerHTML = "";
+
+        for (let slot of slots) {
+            let button = document.createElement("button");
+            button.textContent = slot;
+            button.className =
+                "btn btn-sm mr-1 " + (slot === active ? "btn-success" : "btn-outline-secondary");
+            button.onclick = () => window.tuna.activate(slot);
+            container.appendChild(button);
+        }
     }
 }
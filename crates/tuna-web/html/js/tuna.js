@@ -3,12 +3,14 @@
 class TunaConnection {
     constructor (){
         this.vars = new Vars();
+        this.ab = new AbPanel();
         this.connect();
     }
 
     _recv(msg) {
         let decoded = JSON.parse(msg.data);
         this.vars.addData(decoded);
+        this.ab.addData(decoded);
 
     }
     _send(msg) {
@@ -40,6 +42,7 @@ class TunaConnection {
 
         this.retry_iteration = 0;
         this.listAll();
+        this._send({"AbList": []});
         this.poll = setInterval(this.listAll.bind(this), 5000);
     }
 
@@ -71,6 +74,44 @@ class TunaConnection {
         this.retry_iteration += 1;
     }
 
+    animate(category, name, kind, parameter) {
+        this._send({
+            Animate: [[category, name, { [kind]: [parameter] }]],
+        });
+    }
+
+    stopAnimation(category, name) {
+        this._send({
+            StopAnimation: [[category, name]],
+        });
+    }
+
+    search(query) {
+        this._send({
+            Search: [query],
+        });
+    }
+
+    capture(slot, pattern) {
+        this._send({
+            AbCapture: [[slot, pattern]],
+        });
+    }
+
+    activate(slot) {
+        this._send({
+            AbActivate: [slot],
+        });
+    }
+
+    toggle() {
+        this._send({"AbToggle": []});
+    }
+
+    stats() {
+        this._send({"GetStats": []});
+    }
+
     set(category, name, value) {
         this._send({
             Delta: [[
@@ -1,11 +1,14 @@
 use anyhow::Result;
 use nanoserde::{DeJson, SerJson};
 use std::{
+    io::ErrorKind,
     net::{TcpListener, TcpStream},
     str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 use tiny_http::{Header, Response as HttpResponse, Server};
-use tuna::Tuneable;
+use tuna::{Animator, ChangeSource, Tuneable};
 use tungstenite::{accept, WebSocket};
 
 use include_dir::{include_dir, Dir};
@@ -34,21 +37,182 @@ enum TunaMessage {
     Tuneables(tuna::TunaState),
     Delta((String, String, Tuneable)),
     Ok((String, String)),
+    Error((String, String, String)),
+    Animate((String, String, Animator)),
+    StopAnimation((String, String)),
+    Search(String),
+    SearchResults(Vec<(String, String)>),
+    AbList,
+    AbCapture((String, String)),
+    AbActivate(String),
+    AbToggle,
+    AbSlots((Vec<String>, Option<String>)),
+    GetStats,
+    Stats(Vec<VariableStats>),
+    Sources(Vec<(String, String, String)>),
 }
 
+/// How a variable has been used, see `tuna::stats`
+///
+/// The optional fields come first, as nanoserde leaves a trailing comma when
+/// the last field is `None`.
+#[derive(DeJson, SerJson, Debug)]
+struct VariableStats {
+    category: String,
+    name: String,
+    /// Seconds since the Unix epoch
+    last_read: Option<f64>,
+    last_write: Option<f64>,
+    /// The `ChangeSource` of the last write, as text
+    last_writer: Option<String>,
+    reads: u64,
+    writes: u64,
+}
+
+#[cfg(feature = "stats")]
+fn variable_stats() -> Vec<VariableStats> {
+    let seconds = |time: Option<std::time::SystemTime>| {
+        time.and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64())
+    };
+
+    tuna::all()
+        .into_iter()
+        .map(|variable| VariableStats {
+            category: variable.category,
+            name: variable.name,
+            last_read: seconds(variable.stats.last_read),
+            last_write: seconds(variable.stats.last_write),
+            last_writer: variable.stats.last_writer.map(|source| source.to_string()),
+            reads: variable.stats.reads,
+            writes: variable.stats.writes,
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "stats"))]
+fn variable_stats() -> Vec<VariableStats> {
+    log::warn!("statistics requested, but tuna-web was built without the `stats` feature");
+    vec![]
+}
+
+/// What last changed each variable, for the variables that have been written
+fn sources() -> Vec<(String, String, String)> {
+    tuna::all()
+        .into_iter()
+        .filter_map(|variable| {
+            let source = variable.last_source?.to_string();
+            Some((variable.category, variable.name, source))
+        })
+        .collect()
+}
+
+/// How often a client checks for staged writes that failed to commit, while
+/// waiting for messages
+const FAILURE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 struct TunaClient {
     websocket: WebSocket<TcpStream>,
+    peer: String,
+    /// Writes from this client that failed when committed
+    failures: Arc<Mutex<Vec<(String, String, String)>>>,
+    failure_listener: tuna::ListenerId,
 }
 
 impl TunaClient {
     fn new(stream: TcpStream) -> Result<Self> {
+        let peer = stream.peer_addr()?.to_string();
         let websocket = accept(stream)?;
+        websocket
+            .get_ref()
+            .set_read_timeout(Some(FAILURE_POLL_INTERVAL))?;
+
+        let failures = Arc::new(Mutex::new(vec![]));
+        let source = ChangeSource::Web(peer.clone());
+        let sink = failures.clone();
+        let failure_listener = tuna::add_failure_listener(move |category, name, error, from| {
+            if *from == source {
+                sink.lock().unwrap().push((
+                    category.to_owned(),
+                    name.to_owned(),
+                    error.to_string(),
+                ));
+            }
+        });
+
+        Ok(Self {
+            websocket,
+            peer,
+            failures,
+            failure_listener,
+        })
+    }
+
+    /// Tell the frontend a write failed, sending back the value that is
+    /// actually stored so it can reset the widget.
+    fn send_error(&mut self, category: String, name: String, error: String) {
+        if let Some(current) = tuna::describe(&category, &name).map(|variable| variable.state) {
+            let delta = TunaMessage::Delta((category.clone(), name.clone(), current));
+            self.websocket
+                .write_message(tungstenite::Message::Text(SerJson::serialize_json(&delta)))
+                .unwrap();
+        }
+
+        let response = SerJson::serialize_json(&TunaMessage::Error((category, name, error)));
+        self.websocket
+            .write_message(tungstenite::Message::Text(response))
+            .unwrap();
+    }
+
+    /// Report staged writes from this client that failed when committed
+    fn send_failures(&mut self) {
+        let failures = std::mem::take(&mut *self.failures.lock().unwrap());
+        for (category, name, error) in failures {
+            self.send_error(category, name, error);
+        }
+    }
+
+    fn send_sources(&mut self, sources: Vec<(String, String, String)>) {
+        let sources = TunaMessage::Sources(sources);
+        self.websocket
+            .write_message(tungstenite::Message::Text(SerJson::serialize_json(
+                &sources,
+            )))
+            .unwrap();
+    }
+
+    /// Tell the frontend an A/B operation on `slot` failed. No variable is
+    /// at fault, so the category is left empty, as is the slot for toggling.
+    fn send_slot_error(&mut self, slot: String, error: String) {
+        log::warn!("slot {}: {}", slot, error);
 
-        Ok(Self { websocket })
+        let response = SerJson::serialize_json(&TunaMessage::Error((String::new(), slot, error)));
+        self.websocket
+            .write_message(tungstenite::Message::Text(response))
+            .unwrap();
+    }
+
+    fn send_slots(&mut self) {
+        let slots = TunaMessage::AbSlots((tuna::ab::slots(), tuna::ab::active()));
+        self.websocket
+            .write_message(tungstenite::Message::Text(SerJson::serialize_json(&slots)))
+            .unwrap();
     }
 
     fn poll(&mut self) -> bool {
-        let msg = self.websocket.read_message().unwrap();
+        let msg = match self.websocket.read_message() {
+            Ok(msg) => msg,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                self.send_failures();
+                return true;
+            }
+            Err(e) => {
+                log::debug!("closing connection to {}: {}", self.peer, e);
+                return false;
+            }
+        };
 
         if msg.is_text() {
             let contents = msg.into_text().unwrap();
@@ -62,25 +226,122 @@ impl TunaClient {
 
             match message {
                 TunaMessage::ListAll => {
-                    let state = tuna::TUNA_STATE.read();
-                    let res = TunaMessage::Tuneables((*state).clone());
+                    let mut state = tuna::TunaState::default();
+                    for variable in tuna::all() {
+                        state
+                            .entry(variable.category)
+                            .or_default()
+                            .insert(variable.name, variable.state);
+                    }
+                    let res = TunaMessage::Tuneables(state);
 
                     let response = SerJson::serialize_json(&res);
                     self.websocket
                         .write_message(tungstenite::Message::Text(response))
                         .unwrap();
+                    self.send_sources(sources());
                 }
 
                 TunaMessage::Delta((category, name, tuneable)) => {
-                    tuneable.apply_to(&category, &name);
+                    let source = ChangeSource::Web(self.peer.clone());
+                    match tuneable.apply_from(&category, &name, source) {
+                        Ok(()) => {
+                            // A staged write is only attributed once committed
+                            let source =
+                                tuna::last_source(&category, &name).filter(|_| !tuna::is_staging());
+
+                            let response = SerJson::serialize_json(&TunaMessage::Ok((
+                                category.clone(),
+                                name.clone(),
+                            )));
+                            self.websocket
+                                .write_message(tungstenite::Message::Text(response))
+                                .unwrap();
+
+                            if let Some(source) = source {
+                                self.send_sources(vec![(category, name, source.to_string())]);
+                            }
+                        }
+                        Err(e) => {
+                            log::warn!("failed setting {}/{}: {}", category, name, e);
+                            self.send_error(category, name, e.to_string());
+                        }
+                    }
+                }
+                TunaMessage::Animate((category, name, animator)) => {
+                    let response = match tuna::animate(&category, &name, animator) {
+                        Ok(()) => TunaMessage::Ok((category, name)),
+                        Err(e) => TunaMessage::Error((category, name, e.to_string())),
+                    };
+
+                    let response = SerJson::serialize_json(&response);
+                    self.websocket
+                        .write_message(tungstenite::Message::Text(response))
+                        .unwrap();
+                }
+
+                TunaMessage::StopAnimation((category, name)) => {
+                    tuna::stop_animation(&category, &name);
 
                     let response = SerJson::serialize_json(&TunaMessage::Ok((category, name)));
                     self.websocket
                         .write_message(tungstenite::Message::Text(response))
                         .unwrap();
                 }
-                TunaMessage::Tuneables(_) | TunaMessage::Ok((_, _)) => {
-                    panic!("unexpected message kind")
+
+                TunaMessage::Search(query) => {
+                    let response =
+                        SerJson::serialize_json(&TunaMessage::SearchResults(tuna::search(&query)));
+                    self.websocket
+                        .write_message(tungstenite::Message::Text(response))
+                        .unwrap();
+                }
+
+                TunaMessage::AbList => self.send_slots(),
+
+                TunaMessage::AbCapture((slot, pattern)) => {
+                    let count = tuna::ab::capture(&slot, &pattern);
+                    log::debug!("captured {} variables into slot {}", count, slot);
+                    if count == 0 {
+                        self.send_slot_error(slot, format!("no variables match `{}`", pattern));
+                    }
+                    self.send_slots();
+                }
+
+                TunaMessage::AbActivate(slot) => {
+                    if let Err(e) = tuna::ab::activate(&slot) {
+                        self.send_slot_error(slot, format!("failed activating: {}", e));
+                    }
+                    self.send_slots();
+                }
+
+                TunaMessage::AbToggle => {
+                    if let Err(e) = tuna::ab::toggle() {
+                        self.send_slot_error(String::new(), format!("failed toggling: {}", e));
+                    }
+                    self.send_slots();
+                }
+
+                TunaMessage::GetStats => {
+                    let response = SerJson::serialize_json(&TunaMessage::Stats(variable_stats()));
+                    self.websocket
+                        .write_message(tungstenite::Message::Text(response))
+                        .unwrap();
+                }
+
+                // Only ever sent to clients
+                TunaMessage::Tuneables(_)
+                | TunaMessage::SearchResults(_)
+                | TunaMessage::AbSlots(_)
+                | TunaMessage::Stats(_)
+                | TunaMessage::Sources(_)
+                | TunaMessage::Ok((_, _))
+                | TunaMessage::Error((_, _, _)) => {
+                    log::warn!(
+                        "ignoring unexpected message from {}: {}",
+                        self.peer,
+                        contents
+                    );
                 }
             }
         } else if msg.is_close() {
@@ -93,6 +354,12 @@ impl TunaClient {
     }
 }
 
+impl Drop for TunaClient {
+    fn drop(&mut self) {
+        tuna::remove_listener(self.failure_listener);
+    }
+}
+
 /// The server to tuna web. Will deal with both serving of HTTP content and the
 /// websockets used for management.
 pub struct TunaServer {
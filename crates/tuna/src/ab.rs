@@ -0,0 +1,246 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Named slots holding the values of selected variables, for flipping between
+configurations such as "before" and "after" to compare them.
+
+[`capture`] stores the current values of all variables matching a glob in a
+slot, and [`activate`] writes them back atomically. [`toggle`] switches to the
+slot after the active one, in the order they were first captured, which makes
+it easy to bind to a single key:
+
+```
+use tuna::{ab, Float32};
+
+const SPEED: Float32 = Float32::new("player", "speed", 4.0, None, None);
+SPEED.register();
+
+ab::capture("A", "player.*");
+SPEED.write(6.0);
+ab::capture("B", "player.*");
+
+assert_eq!(ab::toggle().unwrap().as_deref(), Some("A"));
+assert_eq!(SPEED.read(), 4.0);
+assert_eq!(ab::toggle().unwrap().as_deref(), Some("B"));
+assert_eq!(SPEED.read(), 6.0);
+```
+*/
+
+use crate::{
+    compat::{prelude::*, Lazy, Mutex},
+    search::matching_locked,
+    transaction, Boolean, Float32, Float64, Int32, Int64, SetError, Tuneable, TUNA_STATE,
+};
+
+struct Slot {
+    name: String,
+    values: Vec<(String, String, Tuneable)>,
+}
+
+#[derive(Default)]
+struct Slots {
+    slots: Vec<Slot>,
+    active: Option<String>,
+}
+
+static SLOTS: Lazy<Mutex<Slots>> = Lazy::new(|| Mutex::new(Default::default()));
+
+/// Store the current values of all variables matching the glob in `slot`,
+/// replacing anything it held before, and mark it active. Derived variables
+/// are skipped. Returns the number of variables captured.
+pub fn capture(slot: &str, pattern: &str) -> usize {
+    let values = {
+        let tuna = TUNA_STATE.read();
+        matching_locked(&tuna, pattern)
+            .into_iter()
+            .filter_map(|(category, name)| {
+                let tuneable = tuna.get(&category)?.get(&name)?;
+                match tuneable {
+                    Tuneable::Float32(_)
+                    | Tuneable::Float64(_)
+                    | Tuneable::Int32(_)
+                    | Tuneable::Int64(_)
+                    | Tuneable::Boolean(_) => Some((category, name, tuneable.clone())),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let count = values.len();
+
+    let mut slots = SLOTS.lock();
+    match slots.slots.iter_mut().find(|s| s.name == slot) {
+        Some(existing) => existing.values = values,
+        None => slots.slots.push(Slot {
+            name: slot.to_owned(),
+            values,
+        }),
+    }
+    slots.active = Some(slot.to_owned());
+
+    count
+}
+
+/// Write all values held by `slot` in a single transaction, and mark it
+/// active.
+pub fn activate(slot: &str) -> Result<(), SetError> {
+    // Don't hold the lock while listeners run
+    let values = SLOTS
+        .lock()
+        .slots
+        .iter()
+        .find(|s| s.name == slot)
+        .ok_or(SetError::NotFound)?
+        .values
+        .clone();
+
+    transaction(|tx| {
+        for (category, name, tuneable) in &values {
+            match tuneable {
+                Tuneable::Float32(v) => tx.set::<Float32>(category, name, v.current),
+                Tuneable::Float64(v) => tx.set::<Float64>(category, name, v.current),
+                Tuneable::Int32(v) => tx.set::<Int32>(category, name, v.current),
+                Tuneable::Int64(v) => tx.set::<Int64>(category, name, v.current),
+                Tuneable::Boolean(v) => tx.set::<Boolean>(category, name, v.current),
+                _ => continue,
+            };
+        }
+    })?;
+
+    SLOTS.lock().active = Some(slot.to_owned());
+    Ok(())
+}
+
+/// Activate the slot after the active one, wrapping around. Returns the name
+/// of the slot now active, or `None` if there are no slots.
+pub fn toggle() -> Result<Option<String>, SetError> {
+    let next = {
+        let slots = SLOTS.lock();
+        let position = slots
+            .active
+            .as_ref()
+            .and_then(|active| slots.slots.iter().position(|s| &s.name == active));
+
+        match position {
+            Some(i) => slots.slots.get((i + 1) % slots.slots.len()),
+            None => slots.slots.first(),
+        }
+        .map(|s| s.name.clone())
+    };
+
+    match next {
+        Some(slot) => activate(&slot).map(|_| Some(slot)),
+        None => Ok(None),
+    }
+}
+
+/// The names of all slots, in the order they were first captured
+pub fn slots() -> Vec<String> {
+    SLOTS.lock().slots.iter().map(|s| s.name.clone()).collect()
+}
+
+/// The most recently captured or activated slot
+pub fn active() -> Option<String> {
+    SLOTS.lock().active.clone()
+}
+
+/// The variables held by a slot
+pub fn contents(slot: &str) -> Option<Vec<(String, String)>> {
+    SLOTS.lock().slots.iter().find(|s| s.name == slot).map(|s| {
+        s.values
+            .iter()
+            .map(|(category, name, _)| (category.clone(), name.clone()))
+            .collect()
+    })
+}
+
+/// Remove a slot. Returns whether it existed.
+pub fn remove(slot: &str) -> bool {
+    let mut slots = SLOTS.lock();
+    let before = slots.slots.len();
+    slots.slots.retain(|s| s.name != slot);
+
+    if slots.active.as_deref() == Some(slot) {
+        slots.active = None;
+    }
+
+    slots.slots.len() != before
+}
+
+/// Remove all slots
+pub fn clear() {
+    let mut slots = SLOTS.lock();
+    slots.slots.clear();
+    slots.active = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{activate, active, capture, clear, contents, remove, slots, toggle};
+    use crate::compat::prelude::*;
+    use crate::{Boolean, Float32, Int32, SetError};
+    use serial_test::serial;
+
+    const TEST_SPEED: Float32 = Float32::new("ab", "speed", 4.0, None, None);
+    const TEST_JUMPS: Int32 = Int32::new("ab", "jumps", 1, None, None);
+    const TEST_SPRINT: Boolean = Boolean::new("ab", "sprint", false);
+    const TEST_OTHER: Float32 = Float32::new("ab_other", "gravity", 9.81, None, None);
+
+    #[test]
+    #[serial]
+    fn toggle_slots() {
+        clear();
+        TEST_SPEED.register();
+        TEST_JUMPS.register();
+        TEST_SPRINT.register();
+        TEST_OTHER.register();
+        TEST_SPEED.reset();
+        TEST_JUMPS.reset();
+        TEST_SPRINT.reset();
+        TEST_OTHER.reset();
+
+        assert_eq!(toggle(), Ok(None));
+
+        assert_eq!(capture("before", "ab.*"), 3);
+        TEST_SPEED.write(6.0);
+        TEST_JUMPS.write(2);
+        TEST_SPRINT.write(true);
+        assert_eq!(capture("after", "ab.*"), 3);
+        assert_eq!(slots(), vec!["before", "after"]);
+        assert_eq!(active().as_deref(), Some("after"));
+
+        // Variables outside the slot are left alone
+        TEST_OTHER.write(1.0);
+
+        assert_eq!(toggle().unwrap().as_deref(), Some("before"));
+        assert_eq!(TEST_SPEED.read(), 4.0);
+        assert_eq!(TEST_JUMPS.read(), 1);
+        assert!(!TEST_SPRINT.read());
+        assert_eq!(TEST_OTHER.read(), 1.0);
+
+        assert_eq!(toggle().unwrap().as_deref(), Some("after"));
+        assert_eq!(TEST_SPEED.read(), 6.0);
+        assert_eq!(TEST_JUMPS.read(), 2);
+        assert!(TEST_SPRINT.read());
+
+        // Partial slots only hold what they captured
+        assert_eq!(capture("slow", "ab.speed"), 1);
+        assert_eq!(contents("slow"), Some(vec![("ab".into(), "speed".into())]));
+        TEST_SPEED.write(1.0);
+        assert_eq!(toggle().unwrap().as_deref(), Some("before"));
+        assert_eq!(toggle().unwrap().as_deref(), Some("after"));
+        assert_eq!(toggle().unwrap().as_deref(), Some("slow"));
+        assert_eq!(TEST_SPEED.read(), 6.0);
+        assert_eq!(TEST_JUMPS.read(), 2);
+
+        assert!(remove("slow"));
+        assert!(!remove("slow"));
+        assert_eq!(active(), None);
+        assert_eq!(activate("slow"), Err(SetError::NotFound));
+
+        clear();
+        assert!(slots().is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Animators that drive numeric variables over time, e.g. to watch a parameter
+sweep through its range while tuning visuals. Animations advance when the
+application calls [`tick`].
+*/
+
+use crate::{
+    compat::{math, prelude::*, Lazy, Map, Mutex},
+    rng::Rng,
+    SetError, Tuneable, TUNA_STATE,
+};
+#[cfg(feature = "std")]
+use nanoserde::{DeJson, SerJson};
+
+/// The shape of an animation. Values move between the variable's min and max.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Animator {
+    /// A sine wave with the given period in seconds
+    Sine(f64),
+    /// A triangle wave with the given period in seconds
+    Triangle(f64),
+    /// A linear ramp from min to max with the given period in seconds, then
+    /// starting over from min
+    Sweep(f64),
+    /// A random walk, moving at most the given fraction of the range per second
+    RandomWalk(f64),
+}
+
+struct Animation {
+    animator: Animator,
+    range: (f64, f64),
+    time: f64,
+    position: f64,
+    rng: Rng,
+}
+
+impl Animation {
+    fn advance(&mut self, dt: f64) -> f64 {
+        self.time += dt;
+
+        let phase = |period: f64| {
+            if period > 0.0 {
+                math::fract(self.time / period)
+            } else {
+                0.0
+            }
+        };
+
+        let fraction = match self.animator {
+            Animator::Sine(period) => {
+                0.5 - 0.5 * math::cos(phase(period) * 2.0 * core::f64::consts::PI)
+            }
+            Animator::Triangle(period) => 1.0 - (2.0 * phase(period) - 1.0).abs(),
+            Animator::Sweep(period) => phase(period),
+            Animator::RandomWalk(speed) => {
+                let step = (self.rng.next_f64() * 2.0 - 1.0) * speed * dt;
+                self.position = (self.position + step).clamp(0.0, 1.0);
+                self.position
+            }
+        };
+
+        let (lo, hi) = self.range;
+        lo + (hi - lo) * fraction
+    }
+}
+
+static ANIMATIONS: Lazy<Mutex<Map<(String, String), Animation>>> =
+    Lazy::new(|| Mutex::new(Default::default()));
+
+/// The range an animation moves through. Unbounded ends fall back to the
+/// default value plus or minus its magnitude (or one, for a zero default).
+fn range_of(tuneable: &Tuneable) -> Option<((f64, f64), f64)> {
+    fn bounds<T: Into<f64> + Copy>(
+        default: T,
+        min: Option<T>,
+        max: Option<T>,
+        current: T,
+    ) -> ((f64, f64), f64) {
+        let default = default.into();
+        let spread = if default == 0.0 { 1.0 } else { default.abs() };
+        let lo = min.map_or(default - spread, Into::into);
+        let hi = max.map_or(default + spread, Into::into);
+        ((lo, hi), current.into())
+    }
+
+    match tuneable {
+        Tuneable::Float32(v) => Some(bounds(v.default, v.min, v.max, v.current)),
+        Tuneable::Float64(v) => Some(bounds(v.default, v.min, v.max, v.current)),
+        Tuneable::Int32(v) => Some(bounds(v.default, v.min, v.max, v.current)),
+        Tuneable::Int64(v) => Some(bounds(
+            v.default as f64,
+            v.min.map(|v| v as f64),
+            v.max.map(|v| v as f64),
+            v.current as f64,
+        )),
+        _ => None,
+    }
+}
+
+/// Start animating a numeric variable, replacing any running animation on it.
+pub fn animate(category: &str, name: &str, animator: Animator) -> Result<(), SetError> {
+    let (range, current) = {
+        let tuna = TUNA_STATE.read();
+        let tuneable = tuna
+            .get(category)
+            .and_then(|group| group.get(name))
+            .ok_or(SetError::NotFound)?;
+        range_of(tuneable).ok_or(SetError::TypeMismatch)?
+    };
+
+    let (lo, hi) = range;
+    let position = if hi > lo {
+        ((current - lo) / (hi - lo)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    log::debug!("Animating {}/{} with {:?}", category, name, animator);
+    ANIMATIONS.lock().insert(
+        (category.to_owned(), name.to_owned()),
+        Animation {
+            animator,
+            range,
+            time: 0.0,
+            position,
+            rng: Rng::from_str(&format!("{}/{}", category, name)),
+        },
+    );
+
+    Ok(())
+}
+
+/// Stop animating a variable. The variable keeps its last animated value.
+pub fn stop_animation(category: &str, name: &str) {
+    ANIMATIONS
+        .lock()
+        .remove(&(category.to_owned(), name.to_owned()));
+}
+
+/// Check if the variable is being animated
+pub fn is_animating(category: &str, name: &str) -> bool {
+    ANIMATIONS
+        .lock()
+        .contains_key(&(category.to_owned(), name.to_owned()))
+}
+
+/// List all running animations
+pub fn animations() -> Vec<(String, String, Animator)> {
+    ANIMATIONS
+        .lock()
+        .iter()
+        .map(|((category, name), animation)| (category.clone(), name.clone(), animation.animator))
+        .collect()
+}
+
+/// Advance all animations by `dt` seconds and write the new values. Call this
+/// once per frame.
+pub fn tick(dt: f64) {
+    let values = ANIMATIONS
+        .lock()
+        .iter_mut()
+        .map(|((category, name), animation)| {
+            (category.clone(), name.clone(), animation.advance(dt))
+        })
+        .collect::<Vec<_>>();
+
+    for (category, name, value) in values {
+        if let Err(e) =
+            crate::api::try_set_f64(&category, &name, value, crate::ChangeSource::Animation)
+        {
+            log::debug!("Animation of {}/{} failed: {}", category, name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{animate, is_animating, stop_animation, tick, Animator};
+    use crate::{Boolean, Float32, Int32, SetError};
+    use serial_test::serial;
+
+    const TEST_SINE: Float32 = Float32::new("animate", "sine", 0.0, Some(0.0), Some(2.0));
+    const TEST_SWEEP: Int32 = Int32::new("animate", "sweep", 0, Some(0), Some(10));
+    const TEST_WALK: Float32 = Float32::new("animate", "walk", 0.5, Some(0.0), Some(1.0));
+    const TEST_BOOL: Boolean = Boolean::new("animate", "bool", false);
+
+    #[test]
+    #[serial]
+    fn sine() {
+        TEST_SINE.register();
+        TEST_SINE.reset();
+        animate("animate", "sine", Animator::Sine(4.0)).unwrap();
+
+        tick(1.0);
+        assert!((TEST_SINE.read() - 1.0).abs() < 1e-5);
+        tick(1.0);
+        assert!((TEST_SINE.read() - 2.0).abs() < 1e-5);
+
+        stop_animation("animate", "sine");
+        assert!(!is_animating("animate", "sine"));
+        tick(1.0);
+        assert!((TEST_SINE.read() - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    #[serial]
+    fn sweep() {
+        TEST_SWEEP.register();
+        TEST_SWEEP.reset();
+        animate("animate", "sweep", Animator::Sweep(10.0)).unwrap();
+
+        tick(3.0);
+        assert_eq!(TEST_SWEEP.read(), 3);
+        tick(3.0);
+        assert_eq!(TEST_SWEEP.read(), 6);
+
+        stop_animation("animate", "sweep");
+    }
+
+    #[test]
+    #[serial]
+    fn random_walk_stays_in_range() {
+        TEST_WALK.register();
+        animate("animate", "walk", Animator::RandomWalk(5.0)).unwrap();
+
+        for _ in 0..100 {
+            tick(0.1);
+            let v = TEST_WALK.read();
+            assert!((0.0..=1.0).contains(&v));
+        }
+
+        stop_animation("animate", "walk");
+    }
+
+    #[test]
+    #[serial]
+    fn not_numeric() {
+        TEST_BOOL.register();
+        assert_eq!(
+            animate("animate", "bool", Animator::Sine(1.0)),
+            Err(SetError::TypeMismatch)
+        );
+        assert_eq!(
+            animate("animate", "missing", Animator::Sine(1.0)),
+            Err(SetError::NotFound)
+        );
+    }
+}
@@ -5,13 +5,21 @@
 /*!
 
 */
-use std::any::Any;
+use core::any::Any;
 
-use crate::{Tuneable, TUNA_STATE};
+use crate::{
+    args,
+    compat::{math, prelude::*, RwLockWriteGuard},
+    conflict::{self, ConflictPolicy},
+    constraint, derived, listener, snapshot,
+    source::{self, ChangeSource},
+    validate::validate,
+    Float32, Float64, Int32, Int64, TunaState, Tuneable, TUNA_STATE,
+};
 
 /// Implemented by types that can be used to make tuneables; i.e., manipulated state with various constraints
 pub trait AsTuneable: Any + Clone + Sized {
-    type Result: std::fmt::Debug + Copy;
+    type Result: core::fmt::Debug + Copy + Send + 'static;
 
     fn make_tuneable(&self) -> Tuneable;
     fn update(tuneable: &mut Tuneable, var: Self::Result) -> bool;
@@ -20,14 +28,15 @@ pub trait AsTuneable: Any + Clone + Sized {
 }
 
 /// Register a tuneable variable with a default variable. If variable
-/// already exists, won't do anything.
+/// already exists with the same definition, won't do anything; a differing
+/// type, default or range is handled according to the [`ConflictPolicy`].
 pub fn register<T: AsTuneable>(category: &str, name: &str, value: &T) {
     let mut tuna = TUNA_STATE.write();
     log::debug!(
         "Registering variable: {}/{} -> {:?}",
         category,
         name,
-        std::any::type_name::<T>(),
+        core::any::type_name::<T>(),
     );
     if !tuna.contains_key(category) {
         tuna.insert(category.to_owned(), Default::default());
@@ -35,55 +44,348 @@ pub fn register<T: AsTuneable>(category: &str, name: &str, value: &T) {
 
     let group = tuna.get_mut(category).expect("must be inserted above");
 
-    if group.contains_key(name) {
-        return;
+    let tuneable = value.make_tuneable();
+    if let Some(existing) = group.get(name) {
+        let reason = match conflict::compare(existing, &tuneable) {
+            Some(reason) => reason,
+            None => return,
+        };
+
+        match conflict::report(category, name, existing, &tuneable, reason) {
+            ConflictPolicy::Warn => return,
+            ConflictPolicy::PanicInDebug => {
+                drop(tuna);
+                if cfg!(debug_assertions) {
+                    panic!("Conflicting definitions of {}/{}", category, name);
+                }
+                return;
+            }
+            ConflictPolicy::Replace => {
+                // Its expression would be evaluated into the new variable
+                if matches!(existing, Tuneable::Derived(_))
+                    && !matches!(tuneable, Tuneable::Derived(_))
+                {
+                    derived::forget(category, name);
+                }
+            }
+        }
     }
 
-    group.insert(name.to_owned(), value.make_tuneable());
+    group.insert(name.to_owned(), tuneable);
+    let changed = [(category.to_owned(), name.to_owned())];
+    derived::recompute(&mut tuna, &changed);
+    snapshot::bump_epoch(&changed);
+    drop(tuna);
+
+    args::apply_deferred(category, name);
 }
 
 /// Get a the value of tunable variable, if it matches the expected type
 pub fn get<T: AsTuneable>(category: &str, name: &str) -> Option<T::Result> {
-    let tuna = TUNA_STATE.read();
+    get_locked::<T>(&TUNA_STATE.read(), category, name)
+}
+
+/// Like [`get`], from an already locked state, so several variables can be
+/// read consistently
+#[doc(hidden)]
+pub fn get_locked<T: AsTuneable>(
+    tuna: &TunaState,
+    category: &str,
+    name: &str,
+) -> Option<T::Result> {
+    #[cfg(feature = "std")]
+    if let Some(value) = crate::overrides::get::<T>(category, name) {
+        return Some(value);
+    }
+
     let res: Option<T::Result> = tuna
         .get(category)
         .and_then(|group| group.get(name))
         .and_then(|value| T::from_tuneable(value));
 
+    #[cfg(feature = "stats")]
+    if res.is_some() {
+        crate::stats::record_read(category, name);
+    }
+
     log::trace!("Reading variable {}/{} as {:?}", category, name, res);
     res
 }
 
+/// The reason a write to a tuneable was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetError {
+    /// No variable is registered with that category and name
+    NotFound,
+    /// The variable is registered with a different type
+    TypeMismatch,
+    /// A validator rejected the value, with the message it gave
+    Rejected(String),
+    /// The value would violate a constraint between variables
+    Constraint(String),
+    /// The variable is derived from other variables and can't be written
+    ReadOnly,
+    /// The text could not be parsed as the type of the variable
+    Parse(String),
+}
+
+impl core::fmt::Display for SetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SetError::NotFound => write!(f, "unknown tuneable"),
+            SetError::TypeMismatch => write!(f, "tuneable has a different type"),
+            SetError::Rejected(msg) => write!(f, "value rejected: {}", msg),
+            SetError::Constraint(msg) => write!(f, "constraint violated: {}", msg),
+            SetError::ReadOnly => write!(f, "tuneable is read-only"),
+            SetError::Parse(msg) => write!(f, "invalid value: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SetError {}
+
 /// Set a tuneable variable, if it makes the expected type
 pub fn set<T: AsTuneable>(category: &str, name: &str, value: T::Result) -> bool {
+    try_set::<T>(category, name, value).is_ok()
+}
+
+/// Like [`set`], attributing the write to `source` instead of
+/// [`ChangeSource::Code`]
+pub fn set_from<T: AsTuneable>(
+    category: &str,
+    name: &str,
+    value: T::Result,
+    source: ChangeSource,
+) -> bool {
+    try_set_from::<T>(category, name, value, source).is_ok()
+}
+
+/// Set a tuneable variable, running all validators first. Returns the reason
+/// if the value could not be written.
+pub fn try_set<T: AsTuneable>(
+    category: &str,
+    name: &str,
+    value: T::Result,
+) -> Result<(), SetError> {
+    try_set_from::<T>(category, name, value, ChangeSource::Code)
+}
+
+/// Like [`try_set`], attributing the write to `source` instead of
+/// [`ChangeSource::Code`]
+pub fn try_set_from<T: AsTuneable>(
+    category: &str,
+    name: &str,
+    value: T::Result,
+    source: ChangeSource,
+) -> Result<(), SetError> {
+    let value = validated::<T>(category, name, value)?;
+
     let mut tuna = TUNA_STATE.write();
+    let changed = set_locked::<T>(&mut tuna, category, name, value, true)?;
+    source::record(&changed, &source);
+    finish_write(tuna, changed);
 
-    if let Some(tuneable) = tuna.get_mut(category).and_then(|group| group.get_mut(name)) {
-        if T::update(tuneable, value) {
-            log::debug!("Setting variable {}/{} to {:?}", category, name, value);
-            true
-        } else {
-            false
-        }
+    Ok(())
+}
+
+/// Run the validators for a variable, logging rejections.
+pub(crate) fn validated<T: AsTuneable>(
+    category: &str,
+    name: &str,
+    value: T::Result,
+) -> Result<T::Result, SetError> {
+    validate::<T>(category, name, value).map_err(|msg| {
+        log::debug!(
+            "Rejected value {:?} for {}/{}: {}",
+            value,
+            category,
+            name,
+            msg
+        );
+        SetError::Rejected(msg)
+    })
+}
+
+/// Write an already validated value into the locked state. Returns every
+/// variable that changed, which can be more than one if a constraint had to
+/// rebalance other variables.
+pub(crate) fn set_locked<T: AsTuneable>(
+    tuna: &mut TunaState,
+    category: &str,
+    name: &str,
+    value: T::Result,
+    check_constraints: bool,
+) -> Result<Vec<(String, String)>, SetError> {
+    let mut candidate = tuna
+        .get(category)
+        .and_then(|group| group.get(name))
+        .cloned()
+        .ok_or(SetError::NotFound)?;
+
+    if let Tuneable::Derived(_) = candidate {
+        return Err(SetError::ReadOnly);
+    }
+
+    if !T::update(&mut candidate, value) {
+        return Err(SetError::TypeMismatch);
+    }
+
+    let changes = if check_constraints {
+        constraint::check(tuna, category, name, &candidate).map_err(|msg| {
+            log::debug!("Constraint violated by {}/{}: {}", category, name, msg);
+            SetError::Constraint(msg)
+        })?
     } else {
-        false
+        vec![]
+    };
+
+    log::debug!("Setting variable {}/{} to {:?}", category, name, value);
+    let mut changed = vec![];
+    for (category, name, tuneable) in changes.into_iter().chain(core::iter::once((
+        category.to_owned(),
+        name.to_owned(),
+        candidate,
+    ))) {
+        if let Some(slot) = tuna
+            .get_mut(&category)
+            .and_then(|group| group.get_mut(&name))
+        {
+            *slot = tuneable;
+            changed.push((category, name));
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Recompute derived variables after a write, then release the lock and notify
+/// listeners. The source of the changes must already have been recorded.
+pub(crate) fn finish_write(
+    mut tuna: RwLockWriteGuard<'_, TunaState>,
+    changed: Vec<(String, String)>,
+) {
+    derived::recompute(&mut tuna, &changed);
+    snapshot::bump_epoch(&changed);
+
+    #[cfg(feature = "stats")]
+    crate::stats::record_writes(&changed);
+
+    if listener::has_listeners() {
+        let changes = changed
+            .into_iter()
+            .filter_map(|(category, name)| {
+                let tuneable = tuna.get(&category)?.get(&name)?.clone();
+                let source = source::last_source(&category, &name).unwrap_or_default();
+                Some((category, name, tuneable, source))
+            })
+            .collect::<Vec<_>>();
+
+        drop(tuna);
+        listener::notify(&changes);
+    }
+}
+
+/// Set a variable from an `f64`, converting to whatever type it was registered
+/// with. Integers are rounded, and booleans are true from `0.5` up.
+pub(crate) fn try_set_f64(
+    category: &str,
+    name: &str,
+    value: f64,
+    source: ChangeSource,
+) -> Result<(), SetError> {
+    let tuneable = TUNA_STATE
+        .read()
+        .get(category)
+        .and_then(|group| group.get(name))
+        .cloned()
+        .ok_or(SetError::NotFound)?;
+
+    match tuneable {
+        Tuneable::Float32(_) => try_set_from::<Float32>(category, name, value as f32, source),
+        Tuneable::Float64(_) => try_set_from::<Float64>(category, name, value, source),
+        Tuneable::Int32(_) => {
+            try_set_from::<Int32>(category, name, math::round(value) as i32, source)
+        }
+        Tuneable::Int64(_) => {
+            try_set_from::<Int64>(category, name, math::round(value) as i64, source)
+        }
+        Tuneable::Boolean(_) => {
+            try_set_from::<crate::Boolean>(category, name, value >= 0.5, source)
+        }
+        Tuneable::Derived(_) => Err(SetError::ReadOnly),
+        _ => Err(SetError::TypeMismatch),
+    }
+}
+
+/// Set a variable from text, parsed according to the type it was registered
+/// with. Booleans accept `true`, `false`, `1` and `0`.
+pub(crate) fn try_set_str(
+    category: &str,
+    name: &str,
+    value: &str,
+    source: ChangeSource,
+) -> Result<(), SetError> {
+    fn parse<R: core::str::FromStr>(value: &str, kind: &str) -> Result<R, SetError> {
+        value
+            .parse()
+            .map_err(|_| SetError::Parse(format!("`{}` is not a valid {}", value, kind)))
+    }
+
+    let tuneable = TUNA_STATE
+        .read()
+        .get(category)
+        .and_then(|group| group.get(name))
+        .cloned()
+        .ok_or(SetError::NotFound)?;
+
+    let value = value.trim();
+    match tuneable {
+        Tuneable::Float32(_) => {
+            try_set_from::<Float32>(category, name, parse(value, "f32")?, source)
+        }
+        Tuneable::Float64(_) => {
+            try_set_from::<Float64>(category, name, parse(value, "f64")?, source)
+        }
+        Tuneable::Int32(_) => try_set_from::<Int32>(category, name, parse(value, "i32")?, source),
+        Tuneable::Int64(_) => try_set_from::<Int64>(category, name, parse(value, "i64")?, source),
+        Tuneable::Boolean(_) => {
+            let value = match value.to_ascii_lowercase().as_str() {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => parse(value, "bool")?,
+            };
+            try_set_from::<crate::Boolean>(category, name, value, source)
+        }
+        Tuneable::Derived(_) => Err(SetError::ReadOnly),
+        _ => Err(SetError::TypeMismatch),
     }
 }
 
 /// Reset the variable to default value
 pub fn reset<T: AsTuneable>(category: &str, name: &str) {
+    reset_from::<T>(category, name, ChangeSource::Code)
+}
+
+/// Like [`reset`], attributing the write to `source` instead of
+/// [`ChangeSource::Code`]
+pub fn reset_from<T: AsTuneable>(category: &str, name: &str, source: ChangeSource) {
     let mut tuna = TUNA_STATE.write();
 
     if let Some(tuneable) = tuna.get_mut(category).and_then(|group| group.get_mut(name)) {
         T::reset(tuneable);
+
+        let changed = vec![(category.to_owned(), name.to_owned())];
+        source::record(&changed, &source);
+        finish_write(tuna, changed);
     }
 }
 
 /// Check if the value is registered
 pub fn is_registered(category: &str, name: &str) -> bool {
-    let mut tuna = TUNA_STATE.write();
-
-    tuna.get_mut(category)
-        .and_then(|group| group.get_mut(name))
+    TUNA_STATE
+        .read()
+        .get(category)
+        .and_then(|group| group.get(name))
         .is_some()
 }
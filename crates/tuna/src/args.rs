@@ -0,0 +1,248 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Overrides from the command line.
+
+Two forms are recognized, and can be mixed:
+
+```text
+--set render.shadows=false --set physics.gravity=-4.5
++set render.shadows false +set physics.gravity -4.5
+```
+
+Values are parsed according to the type of the variable. Overrides for
+variables that aren't registered yet are kept, and applied as soon as the
+variable is registered.
+
+```rust
+let report = tuna::args::apply(std::env::args());
+for (arg, error) in &report.failed {
+    eprintln!("ignoring {}: {}", arg, error);
+}
+```
+*/
+
+use crate::{
+    api::try_set_str,
+    compat::{prelude::*, Lazy, Mutex},
+    ChangeSource, SetError,
+};
+
+/// The outcome of [`apply`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArgsReport {
+    /// The arguments that weren't overrides, in their original order
+    pub remaining: Vec<String>,
+    /// The variables that were set, as category and name
+    pub applied: Vec<(String, String)>,
+    /// Variables that aren't registered yet; they'll be set when registered
+    pub deferred: Vec<(String, String)>,
+    /// Overrides that were malformed, couldn't be parsed or were refused, as
+    /// the `category.name=value` text that was given
+    pub failed: Vec<(String, SetError)>,
+}
+
+struct Deferred {
+    category: String,
+    name: String,
+    value: String,
+}
+
+static DEFERRED: Lazy<Mutex<Vec<Deferred>>> = Lazy::new(|| Mutex::new(Default::default()));
+
+fn split(path: &str) -> Result<(&str, &str), SetError> {
+    match path.split_once('.') {
+        Some((category, name)) if !category.is_empty() && !name.is_empty() => Ok((category, name)),
+        _ => Err(SetError::Parse(format!(
+            "`{}` is not of the form category.name",
+            path
+        ))),
+    }
+}
+
+/// Extract and apply all overrides from the arguments, returning everything
+/// else untouched. Pass the arguments including the program name; it's
+/// returned as the first remaining argument.
+pub fn apply<S: Into<String>>(args: impl IntoIterator<Item = S>) -> ArgsReport {
+    let mut report = ArgsReport::default();
+    let mut args = args.into_iter().map(Into::into);
+
+    while let Some(arg) = args.next() {
+        // The override as `category.name=value`, or whatever was given of it
+        let (text, parsed) = match arg.as_str() {
+            "--set" => match args.next() {
+                Some(assignment) => {
+                    let parsed = match assignment.split_once('=') {
+                        Some((path, value)) => Ok((path.to_owned(), value.to_owned())),
+                        None => Err(SetError::Parse(format!(
+                            "`{}` is not of the form category.name=value",
+                            assignment
+                        ))),
+                    };
+                    (assignment, parsed)
+                }
+                None => (
+                    arg,
+                    Err(SetError::Parse("expected category.name=value".to_owned())),
+                ),
+            },
+            "+set" => match (args.next(), args.next()) {
+                (Some(path), Some(value)) => (format!("{}={}", path, value), Ok((path, value))),
+                (path, _) => (
+                    path.unwrap_or(arg),
+                    Err(SetError::Parse(
+                        "expected category.name and a value".to_owned(),
+                    )),
+                ),
+            },
+            _ => {
+                report.remaining.push(arg);
+                continue;
+            }
+        };
+
+        let result = parsed.and_then(|(path, value)| {
+            let (category, name) = split(&path)?;
+            match try_set_str(category, name, &value, ChangeSource::Args) {
+                Err(SetError::NotFound) => {
+                    log::debug!("Deferring {}/{} until registered", category, name);
+                    DEFERRED.lock().push(Deferred {
+                        category: category.to_owned(),
+                        name: name.to_owned(),
+                        value,
+                    });
+                    report.deferred.push((category.to_owned(), name.to_owned()));
+                    Ok(())
+                }
+                Err(e) => Err(e),
+                Ok(()) => {
+                    report.applied.push((category.to_owned(), name.to_owned()));
+                    Ok(())
+                }
+            }
+        });
+
+        if let Err(e) = result {
+            log::warn!("Failed applying override `{}`: {}", text, e);
+            report.failed.push((text, e));
+        }
+    }
+
+    report
+}
+
+/// The overrides still waiting for their variable to be registered, as
+/// category and name
+pub fn deferred() -> Vec<(String, String)> {
+    DEFERRED
+        .lock()
+        .iter()
+        .map(|d| (d.category.clone(), d.name.clone()))
+        .collect()
+}
+
+/// Drop all overrides still waiting for their variable to be registered
+pub fn clear_deferred() {
+    DEFERRED.lock().clear();
+}
+
+/// Apply any deferred overrides for a variable that was just registered.
+pub(crate) fn apply_deferred(category: &str, name: &str) {
+    let due = {
+        let mut deferred = DEFERRED.lock();
+        if deferred.is_empty() {
+            return;
+        }
+
+        let (due, rest) = core::mem::take(&mut *deferred)
+            .into_iter()
+            .partition::<Vec<_>, _>(|d| d.category == category && d.name == name);
+        *deferred = rest;
+        due
+    };
+
+    for d in due {
+        if let Err(e) = try_set_str(category, name, &d.value, ChangeSource::Args) {
+            log::warn!(
+                "Failed applying deferred override {}/{}: {}",
+                category,
+                name,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, deferred};
+    use crate::compat::prelude::*;
+    use crate::{Boolean, Float32, Int32, SetError};
+    use serial_test::serial;
+
+    const TEST_SHADOWS: Boolean = Boolean::new("args_render", "shadows", true);
+    const TEST_GRAVITY: Float32 = Float32::new("args_physics", "gravity", -9.81, None, None);
+    const TEST_LATE: Int32 = Int32::new("args_late", "count", 1, None, None);
+
+    #[test]
+    #[serial]
+    fn overrides() {
+        TEST_SHADOWS.register();
+        TEST_GRAVITY.register();
+        TEST_SHADOWS.reset();
+        TEST_GRAVITY.reset();
+
+        let report = apply(vec![
+            "game",
+            "--set",
+            "args_render.shadows=false",
+            "--fullscreen",
+            "+set",
+            "args_physics.gravity",
+            "-4.5",
+            "--set",
+            "args_physics.gravity",
+            "level1",
+        ]);
+
+        assert!(!TEST_SHADOWS.read());
+        assert_eq!(TEST_GRAVITY.read(), -4.5);
+        assert_eq!(report.remaining, vec!["game", "--fullscreen", "level1"]);
+        assert_eq!(report.applied.len(), 2);
+        assert!(matches!(
+            report.failed.as_slice(),
+            [(arg, SetError::Parse(_))] if arg == "args_physics.gravity"
+        ));
+
+        let report = apply(vec![
+            "--set",
+            "args_physics.gravity=down",
+            "+set",
+            "args_render.shadows",
+            "maybe",
+        ]);
+        assert!(matches!(
+            report.failed.as_slice(),
+            [(first, SetError::Parse(_)), (second, SetError::Parse(_))]
+                if first == "args_physics.gravity=down" && second == "args_render.shadows=maybe"
+        ));
+        assert_eq!(TEST_GRAVITY.read(), -4.5);
+    }
+
+    #[test]
+    #[serial]
+    fn deferred_until_registered() {
+        let report = apply(vec!["+set", "args_late.count", "7"]);
+        assert_eq!(
+            report.deferred,
+            vec![("args_late".to_owned(), "count".to_owned())]
+        );
+        assert_eq!(deferred().len(), 1);
+
+        TEST_LATE.register();
+        assert_eq!(TEST_LATE.read(), 7);
+        assert!(deferred().is_empty());
+    }
+}
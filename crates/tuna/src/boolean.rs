@@ -6,6 +6,9 @@
 
 */
 
+use crate::compat::prelude::*;
+
+#[cfg(feature = "std")]
 use nanoserde::{DeJson, SerJson};
 
 /// The definition of a boolean variable
@@ -14,10 +17,13 @@ pub struct Boolean {
     pub(crate) category: &'static str,
     pub(crate) name: &'static str,
     pub(crate) default: bool,
+    pub(crate) description: Option<&'static str>,
 }
 
 /// The state of a boolean variablep
-#[derive(Copy, Clone, Debug, SerJson, DeJson)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BooleanVariable {
     pub default: bool,
     pub current: bool,
@@ -30,24 +36,48 @@ impl Boolean {
             category,
             name,
             default,
+            description: None,
+        }
+    }
+
+    /// Attach a description, shown by tools and exported in the schema
+    pub const fn with_description(self, description: &'static str) -> Self {
+        Self {
+            description: Some(description),
+            ..self
         }
     }
 
     /// Explicitly register the boolean with tuna. This is not required, but
     /// it'll reduce risk of stuttering when variables get registered.
     pub fn register(&self) {
-        crate::register(self.category, self.name, self)
+        crate::register(self.category, self.name, self);
+
+        if let Some(description) = self.description {
+            crate::set_description(self.category, self.name, description);
+        }
     }
 
     /// Read the variable from tuna. This will automatically call register on a
-    /// lookup miss, and return the default value.
+    /// lookup miss, and return the default value. If the name is registered
+    /// with another type, the default is returned without registering.
     pub fn read(&self) -> bool {
         crate::get::<Boolean>(self.category, self.name).unwrap_or_else(|| {
-            self.register();
+            if !crate::is_registered(self.category, self.name) {
+                self.register();
+            }
             self.default
         })
     }
 
+    /// Read the variable from a snapshot without locking. Returns the default
+    /// value if it wasn't registered when the snapshot was taken.
+    pub fn read_in(&self, snapshot: &crate::Snapshot) -> bool {
+        snapshot
+            .get::<Boolean>(self.category, self.name)
+            .unwrap_or(self.default)
+    }
+
     /// Update the stored value. Will register and warn if not registered already in debug builds.
     pub fn write(&self, value: bool) {
         #[cfg(debug_assertions)]
@@ -63,6 +93,22 @@ impl Boolean {
     pub fn reset(&self) {
         crate::reset::<Boolean>(self.category, self.name);
     }
+
+    /// Override the value for the current thread until the guard is dropped,
+    /// e.g. to force a value in a test.
+    #[cfg(feature = "std")]
+    pub fn override_scoped(&self, value: bool) -> crate::OverrideGuard {
+        crate::overrides::push(self.category, self.name, self, value)
+    }
+
+    /// Attach a validator that runs on every write to this variable. It can
+    /// adjust the value or reject it with a message.
+    pub fn add_validator(
+        &self,
+        validator: impl Fn(bool) -> Result<bool, String> + Send + Sync + 'static,
+    ) {
+        crate::add_validator::<Boolean, _>(self.category, self.name, validator);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,67 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+The parts of `std` that are swapped out when building without the `std`
+feature: the `alloc` prelude, locks, maps and float math.
+*/
+
+pub(crate) mod prelude {
+    pub(crate) use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec, vec::Vec};
+}
+
+#[cfg(feature = "std")]
+pub(crate) use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{Mutex, RwLock, RwLockWriteGuard};
+
+pub(crate) use spin::Lazy;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::collections::BTreeMap as Map;
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashMap as Map;
+
+#[cfg(feature = "std")]
+pub(crate) mod math {
+    pub(crate) fn round(x: f64) -> f64 {
+        x.round()
+    }
+
+    pub(crate) fn floor(x: f64) -> f64 {
+        x.floor()
+    }
+
+    pub(crate) fn ceil(x: f64) -> f64 {
+        x.ceil()
+    }
+
+    pub(crate) fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+
+    pub(crate) fn pow(x: f64, y: f64) -> f64 {
+        x.powf(y)
+    }
+
+    pub(crate) fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+
+    pub(crate) fn fract(x: f64) -> f64 {
+        x.fract()
+    }
+}
+
+#[cfg(all(not(feature = "std"), not(feature = "libm")))]
+compile_error!("`tuna` needs either the `std` or the `libm` feature for float math");
+
+#[cfg(all(not(feature = "std"), feature = "libm"))]
+pub(crate) mod math {
+    pub(crate) use libm::{ceil, cos, floor, pow, round, sqrt};
+
+    pub(crate) fn fract(x: f64) -> f64 {
+        x - libm::trunc(x)
+    }
+}
@@ -0,0 +1,306 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Detection of conflicting definitions registered under the same name.
+
+Registering a name that already exists used to be silently ignored, so a
+variable defined as both a `Boolean` and a `Float32` would read its default
+forever from one of the definitions. Now the type, default, range, soft range
+and range mode of the two definitions are compared, and a mismatch is handled
+according to the [`ConflictPolicy`] and recorded for [`conflicts`].
+
+Reading through a definition of another type than the registered one returns
+its default without registering it again, so only explicit registration
+replaces a variable.
+*/
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{
+    compat::{prelude::*, Lazy, Mutex},
+    introspect::definition,
+    Kind, RangeMode, Tuneable,
+};
+
+/// What to do when a definition conflicts with the registered one
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the registered variable and log a warning
+    Warn,
+    /// Like `Warn` in release builds, but panic in debug builds
+    PanicInDebug,
+    /// Replace the registered variable with the new definition, resetting
+    /// its value
+    Replace,
+}
+
+/// Two definitions of the same variable that don't agree
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    pub category: String,
+    pub name: String,
+    /// The type of the variable that was registered first
+    pub existing: Kind,
+    /// The type of the conflicting definition
+    pub new: Kind,
+    /// What differs between the two
+    pub reason: String,
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(ConflictPolicy::Warn as u8);
+static CONFLICTS: Lazy<Mutex<Vec<Conflict>>> = Lazy::new(|| Mutex::new(Default::default()));
+
+/// Choose how conflicting registrations are handled. Defaults to
+/// [`ConflictPolicy::Warn`].
+pub fn set_conflict_policy(policy: ConflictPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// The current way conflicting registrations are handled, see
+/// [`set_conflict_policy`]
+pub fn conflict_policy() -> ConflictPolicy {
+    match POLICY.load(Ordering::Relaxed) {
+        0 => ConflictPolicy::Warn,
+        1 => ConflictPolicy::PanicInDebug,
+        _ => ConflictPolicy::Replace,
+    }
+}
+
+/// All conflicts seen since startup, or since [`clear_conflicts`], each once
+pub fn conflicts() -> Vec<Conflict> {
+    CONFLICTS.lock().clone()
+}
+
+/// Forget all recorded conflicts, so each is reported again the next time
+/// it's seen
+pub fn clear_conflicts() {
+    CONFLICTS.lock().clear();
+}
+
+/// How a numeric variable treats values outside of its range
+fn mode(tuneable: &Tuneable) -> Option<RangeMode> {
+    match tuneable {
+        Tuneable::Float32(v) => Some(v.mode()),
+        Tuneable::Float64(v) => Some(v.mode()),
+        Tuneable::Int32(v) => Some(v.mode()),
+        Tuneable::Int64(v) => Some(v.mode()),
+        _ => None,
+    }
+}
+
+/// Describe how two definitions differ, if they do
+pub(crate) fn compare(existing: &Tuneable, new: &Tuneable) -> Option<String> {
+    if existing.kind() != new.kind() {
+        return Some(format!(
+            "registered as {} but defined as {}",
+            existing.kind().as_str(),
+            new.kind().as_str()
+        ));
+    }
+
+    let (old_default, [old_min, old_max, old_soft_min, old_soft_max]) = definition(existing);
+    let (new_default, [new_min, new_max, new_soft_min, new_soft_max]) = definition(new);
+
+    let mut differences = [
+        ("default", old_default, new_default),
+        ("min", old_min, new_min),
+        ("max", old_max, new_max),
+        ("soft_min", old_soft_min, new_soft_min),
+        ("soft_max", old_soft_max, new_soft_max),
+    ]
+    .iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(what, old, new)| format!("{} {:?} != {:?}", what, old, new))
+    .collect::<Vec<_>>();
+
+    if let (Some(old_mode), Some(new_mode)) = (mode(existing), mode(new)) {
+        if old_mode != new_mode {
+            differences.push(format!("mode {:?} != {:?}", old_mode, new_mode));
+        }
+    }
+
+    if differences.is_empty() {
+        None
+    } else {
+        Some(differences.join(", "))
+    }
+}
+
+/// Record a conflict, warning the first time it's seen. Returns the policy to
+/// apply.
+pub(crate) fn report(
+    category: &str,
+    name: &str,
+    existing: &Tuneable,
+    new: &Tuneable,
+    reason: String,
+) -> ConflictPolicy {
+    let conflict = Conflict {
+        category: category.to_owned(),
+        name: name.to_owned(),
+        existing: existing.kind(),
+        new: new.kind(),
+        reason,
+    };
+
+    let mut conflicts = CONFLICTS.lock();
+    if !conflicts.contains(&conflict) {
+        log::warn!(
+            "Conflicting definitions of {}/{}: {}",
+            category,
+            name,
+            conflict.reason
+        );
+        conflicts.push(conflict);
+    }
+
+    conflict_policy()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_conflicts, conflicts, set_conflict_policy, ConflictPolicy};
+    use crate::compat::prelude::*;
+    use crate::{register_derived, Boolean, Derived, Float32, Kind};
+    use serial_test::serial;
+
+    const TEST_FLAG: Boolean = Boolean::new("conflict", "flag", true);
+    const TEST_FLAG_AS_FLOAT: Float32 = Float32::new("conflict", "flag", 0.5, None, None);
+    const TEST_SPEED: Float32 = Float32::new("conflict", "speed", 1.0, Some(0.0), None);
+    const TEST_SPEED_AGAIN: Float32 = Float32::new("conflict", "speed", 1.0, Some(0.0), None);
+    const TEST_SPEED_OTHER: Float32 = Float32::new("conflict", "speed", 2.0, Some(0.0), None);
+    const TEST_SPEED_SOFT: Float32 =
+        Float32::new("conflict", "speed", 1.0, Some(0.0), None).with_soft_range(None, Some(5.0));
+    const TEST_SPEED_WRAP: Float32 =
+        Float32::new("conflict", "speed", 1.0, Some(0.0), None).wrapping();
+
+    #[test]
+    #[serial]
+    fn warn() {
+        set_conflict_policy(ConflictPolicy::Warn);
+        clear_conflicts();
+        TEST_FLAG.register();
+        TEST_SPEED.register();
+
+        // Identical definitions are fine
+        TEST_SPEED_AGAIN.register();
+        assert!(conflicts().is_empty());
+
+        TEST_FLAG_AS_FLOAT.register();
+        TEST_FLAG_AS_FLOAT.register();
+        TEST_SPEED_OTHER.register();
+
+        let found = conflicts();
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            (found[0].existing, found[0].new),
+            (Kind::Boolean, Kind::Float32)
+        );
+        assert_eq!(found[0].reason, "registered as bool but defined as f32");
+        assert_eq!(found[1].reason, "default Some(1.0) != Some(2.0)");
+
+        // The first definition is kept
+        assert!(TEST_FLAG.read());
+        assert_eq!(TEST_SPEED.read(), 1.0);
+        clear_conflicts();
+    }
+
+    #[test]
+    #[serial]
+    fn replace() {
+        set_conflict_policy(ConflictPolicy::Replace);
+        clear_conflicts();
+        TEST_SPEED.register();
+        TEST_SPEED_OTHER.register();
+        assert_eq!(TEST_SPEED.read(), 2.0);
+        assert_eq!(conflicts().len(), 1);
+
+        TEST_SPEED.register();
+        assert_eq!(TEST_SPEED.read(), 1.0);
+
+        set_conflict_policy(ConflictPolicy::Warn);
+        clear_conflicts();
+    }
+
+    const TEST_RATIO: Derived =
+        Derived::new("conflict", "ratio", "conflict.input * 2 + conflict.cycle");
+    const TEST_RATIO_PLAIN: Float32 = Float32::new("conflict", "ratio", 0.5, None, None);
+    const TEST_INPUT: Float32 = Float32::new("conflict", "input", 1.0, None, None);
+
+    #[test]
+    #[serial]
+    fn replace_derived() {
+        set_conflict_policy(ConflictPolicy::Replace);
+        clear_conflicts();
+        TEST_INPUT.register();
+        TEST_RATIO.register();
+
+        TEST_RATIO_PLAIN.register();
+        TEST_INPUT.write(3.0);
+        assert_eq!(TEST_RATIO_PLAIN.read(), 0.5);
+
+        // The old expression is gone, so it can't form a cycle
+        set_conflict_policy(ConflictPolicy::Warn);
+        assert!(register_derived("conflict", "cycle", "conflict.ratio + 1").is_ok());
+
+        clear_conflicts();
+        TEST_INPUT.reset();
+    }
+
+    #[test]
+    #[serial]
+    fn read_both_definitions() {
+        set_conflict_policy(ConflictPolicy::Replace);
+        clear_conflicts();
+        TEST_FLAG.register();
+        TEST_FLAG.write(false);
+
+        // Reading doesn't register, so the definitions don't keep replacing
+        // each other and resetting the value
+        for _ in 0..3 {
+            assert!(!TEST_FLAG.read());
+            assert_eq!(TEST_FLAG_AS_FLOAT.read(), 0.5);
+        }
+        assert!(!TEST_FLAG.read());
+        assert!(conflicts().is_empty());
+
+        set_conflict_policy(ConflictPolicy::Warn);
+        TEST_FLAG.reset();
+    }
+
+    #[test]
+    #[serial]
+    fn soft_range_and_mode() {
+        set_conflict_policy(ConflictPolicy::Warn);
+        clear_conflicts();
+        TEST_SPEED.register();
+        TEST_SPEED_SOFT.register();
+        TEST_SPEED_WRAP.register();
+
+        let reasons = conflicts()
+            .into_iter()
+            .map(|c| c.reason)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            vec!["soft_max None != Some(5.0)", "mode Clamp != Wrap"]
+        );
+        clear_conflicts();
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(debug_assertions)]
+    fn panic_in_debug() {
+        set_conflict_policy(ConflictPolicy::PanicInDebug);
+        TEST_FLAG.register();
+
+        let result = std::panic::catch_unwind(|| TEST_FLAG_AS_FLOAT.register());
+        set_conflict_policy(ConflictPolicy::Warn);
+        clear_conflicts();
+
+        assert!(result.is_err());
+    }
+}
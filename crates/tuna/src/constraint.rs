@@ -0,0 +1,477 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Relations between variables, checked while the state is locked for writing so
+a write either satisfies all constraints or doesn't happen at all.
+*/
+
+use crate::compat::{math, prelude::*, Lazy, RwLock};
+
+use crate::{validate::validate, AsTuneable, Float32, Float64, Int32, Int64, TunaState, Tuneable};
+
+/// A relation that must hold between two or more variables, each given as
+/// `(category, name)`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constraint {
+    /// Each numeric variable must be strictly less than the next one.
+    /// Booleans and derived variables can't be members, and make every write
+    /// to the other members fail.
+    Ordered(Vec<(String, String)>),
+    /// The numeric variables must sum to one. Writing one member rescales the
+    /// others proportionally to keep the sum. Like `Ordered`, all members must
+    /// be writable numbers.
+    SumToOne(Vec<(String, String)>),
+    /// At most one of the boolean variables may be true.
+    MutuallyExclusive(Vec<(String, String)>),
+}
+
+fn to_owned(variables: &[(&str, &str)]) -> Vec<(String, String)> {
+    variables
+        .iter()
+        .map(|(category, name)| ((*category).to_owned(), (*name).to_owned()))
+        .collect()
+}
+
+impl Constraint {
+    /// Require that the variables are strictly increasing, in the given order.
+    pub fn ordered(variables: &[(&str, &str)]) -> Self {
+        Self::Ordered(to_owned(variables))
+    }
+
+    /// Require that the variables sum to one.
+    pub fn sum_to_one(variables: &[(&str, &str)]) -> Self {
+        Self::SumToOne(to_owned(variables))
+    }
+
+    /// Require that at most one of the variables is true.
+    pub fn mutually_exclusive(variables: &[(&str, &str)]) -> Self {
+        Self::MutuallyExclusive(to_owned(variables))
+    }
+
+    fn variables(&self) -> &[(String, String)] {
+        match self {
+            Self::Ordered(v) | Self::SumToOne(v) | Self::MutuallyExclusive(v) => v,
+        }
+    }
+
+    fn involves(&self, category: &str, name: &str) -> bool {
+        self.variables()
+            .iter()
+            .any(|(c, n)| c == category && n == name)
+    }
+}
+
+static CONSTRAINTS: Lazy<RwLock<Vec<Constraint>>> = Lazy::new(|| RwLock::new(Default::default()));
+
+/// Add a constraint. It is checked on every following write to any of the
+/// involved variables. Registering and resetting variables don't check
+/// constraints, so a reset can leave one violated until the next write.
+pub fn add_constraint(constraint: Constraint) {
+    log::debug!("Adding constraint {:?}", constraint);
+    CONSTRAINTS.write().push(constraint);
+}
+
+/// Remove all constraints
+pub fn clear_constraints() {
+    CONSTRAINTS.write().clear();
+}
+
+const SUM_TOLERANCE: f64 = 1e-4;
+
+fn as_bool(tuneable: &Tuneable) -> Option<bool> {
+    match tuneable {
+        Tuneable::Boolean(v) => Some(v.current),
+        _ => None,
+    }
+}
+
+/// The value of a member of an `Ordered` or `SumToOne` constraint, which must
+/// be a writable number
+fn as_number(tuneable: &Tuneable, category: &str, name: &str) -> Result<f64, String> {
+    match tuneable {
+        Tuneable::Float32(v) => Ok(v.current as f64),
+        Tuneable::Float64(v) => Ok(v.current),
+        Tuneable::Int32(v) => Ok(v.current as f64),
+        Tuneable::Int64(v) => Ok(v.current as f64),
+        _ => Err(format!("{}/{} is not numeric", category, name)),
+    }
+}
+
+/// Write a rebalanced value, running the validators of the variable like any
+/// other write would
+fn rebalance(
+    tuneable: &mut Tuneable,
+    category: &str,
+    name: &str,
+    value: f64,
+) -> Result<(), String> {
+    fn apply<T: AsTuneable>(
+        tuneable: &mut Tuneable,
+        category: &str,
+        name: &str,
+        value: T::Result,
+    ) -> Result<(), String> {
+        let value = validate::<T>(category, name, value)
+            .map_err(|msg| format!("rebalancing {}/{} was rejected: {}", category, name, msg))?;
+        T::update(tuneable, value);
+        Ok(())
+    }
+
+    match tuneable {
+        Tuneable::Float32(_) => apply::<Float32>(tuneable, category, name, value as f32),
+        Tuneable::Float64(_) => apply::<Float64>(tuneable, category, name, value),
+        Tuneable::Int32(_) => apply::<Int32>(tuneable, category, name, math::round(value) as i32),
+        Tuneable::Int64(_) => apply::<Int64>(tuneable, category, name, math::round(value) as i64),
+        _ => Err(format!("{}/{} is not numeric", category, name)),
+    }
+}
+
+/// Check all constraints involving `category/name` as if it had the value
+/// `candidate`. On success, returns the other variables that have to change
+/// along with it.
+pub(crate) fn check(
+    state: &TunaState,
+    category: &str,
+    name: &str,
+    candidate: &Tuneable,
+) -> Result<Vec<(String, String, Tuneable)>, String> {
+    // Cloned so the lock isn't held when verifying the result below
+    let constraints = CONSTRAINTS
+        .read()
+        .iter()
+        .filter(|c| c.involves(category, name))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut changes = vec![];
+
+    let lookup = |c: &str, n: &str| -> Option<&Tuneable> {
+        if c == category && n == name {
+            Some(candidate)
+        } else {
+            state.get(c).and_then(|group| group.get(n))
+        }
+    };
+
+    for constraint in &constraints {
+        match constraint {
+            Constraint::Ordered(vars) => check_ordered(vars, &lookup)?,
+
+            Constraint::SumToOne(vars) => {
+                let value = as_number(candidate, category, name)?;
+                let others = vars
+                    .iter()
+                    .filter(|(c, n)| !(c == category && n == name))
+                    .filter_map(|(c, n)| {
+                        let t = state.get(c).and_then(|group| group.get(n))?;
+                        Some(as_number(t, c, n).map(|v| (c, n, t, v)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let rest: f64 = others.iter().map(|(_, _, _, v)| v).sum();
+                let remaining = 1.0 - value;
+
+                if remaining < -SUM_TOLERANCE || (rest <= 0.0 && remaining > SUM_TOLERANCE) {
+                    return Err(format!(
+                        "{}/{} ({}) cannot be balanced to sum to one",
+                        category, name, value
+                    ));
+                }
+
+                for (c, n, t, v) in others {
+                    let mut t = t.clone();
+                    let scaled = if rest > 0.0 {
+                        v * remaining / rest
+                    } else {
+                        0.0
+                    };
+                    rebalance(&mut t, c, n, scaled.max(0.0))?;
+                    changes.push((c.clone(), n.clone(), t));
+                }
+            }
+
+            Constraint::MutuallyExclusive(vars) => {
+                if as_bool(candidate) != Some(true) {
+                    continue;
+                }
+
+                let conflict = vars
+                    .iter()
+                    .filter(|(c, n)| !(c == category && n == name))
+                    .find(|(c, n)| lookup(c, n).and_then(as_bool) == Some(true));
+
+                if let Some((c, n)) = conflict {
+                    return Err(format!(
+                        "{}/{} cannot be enabled while {}/{} is enabled",
+                        category, name, c, n
+                    ));
+                }
+            }
+        }
+    }
+
+    if !changes.is_empty() {
+        // Rebalanced values are clamped, rounded and validated, and may be
+        // part of other constraints, so check everything against the result
+
+        let mut touched = vec![(category.to_owned(), name.to_owned())];
+        touched.extend(changes.iter().map(|(c, n, _)| (c.clone(), n.clone())));
+
+        verify_with(
+            |c, n| {
+                changes
+                    .iter()
+                    .find(|(oc, on, _)| oc == c && on == n)
+                    .map(|(_, _, t)| t)
+                    .or_else(|| lookup(c, n))
+            },
+            &touched,
+        )?;
+    }
+
+    Ok(changes)
+}
+
+fn check_ordered<'a>(
+    vars: &[(String, String)],
+    lookup: &impl Fn(&str, &str) -> Option<&'a Tuneable>,
+) -> Result<(), String> {
+    let values = vars
+        .iter()
+        .map(|(c, n)| {
+            lookup(c, n)
+                .map(|t| as_number(t, c, n).map(|v| (c, n, v)))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for pair in values.windows(2) {
+        if let [Some((lc, ln, lower)), Some((uc, un, upper))] = pair {
+            if lower >= upper {
+                return Err(format!(
+                    "{}/{} ({}) must be less than {}/{} ({})",
+                    lc, ln, lower, uc, un, upper
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check all constraints involving any of the `touched` variables against the
+/// state as it is, without rebalancing anything. Used to check a set of writes
+/// that were applied together.
+pub(crate) fn verify(state: &TunaState, touched: &[(String, String)]) -> Result<(), String> {
+    verify_with(|c, n| state.get(c).and_then(|group| group.get(n)), touched)
+}
+
+fn verify_with<'a>(
+    lookup: impl Fn(&str, &str) -> Option<&'a Tuneable>,
+    touched: &[(String, String)],
+) -> Result<(), String> {
+    let constraints = CONSTRAINTS.read();
+
+    for constraint in constraints.iter().filter(|c| {
+        touched
+            .iter()
+            .any(|(category, name)| c.involves(category, name))
+    }) {
+        match constraint {
+            Constraint::Ordered(vars) => check_ordered(vars, &lookup)?,
+
+            Constraint::SumToOne(vars) => {
+                let sum = vars
+                    .iter()
+                    .filter_map(|(c, n)| lookup(c, n).map(|t| as_number(t, c, n)))
+                    .sum::<Result<f64, _>>()?;
+
+                if (sum - 1.0).abs() > SUM_TOLERANCE {
+                    return Err(format!("weights sum to {}, not one", sum));
+                }
+            }
+
+            Constraint::MutuallyExclusive(vars) => {
+                let enabled = vars
+                    .iter()
+                    .filter(|(c, n)| lookup(c, n).and_then(as_bool) == Some(true))
+                    .map(|(c, n)| format!("{}/{}", c, n))
+                    .collect::<Vec<_>>();
+
+                if enabled.len() > 1 {
+                    return Err(format!("only one of {} may be enabled", enabled.join(", ")));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_constraint, clear_constraints, Constraint};
+    use crate::compat::prelude::*;
+    use crate::{Boolean, Float32, SetError};
+    use serial_test::serial;
+
+    const TEST_NEAR: Float32 = Float32::new("constraint", "near", 0.1, Some(0.0), None);
+    const TEST_FAR: Float32 = Float32::new("constraint", "far", 100.0, Some(0.0), None);
+
+    const TEST_WEIGHT_A: Float32 =
+        Float32::new("constraint", "weight_a", 0.5, Some(0.0), Some(1.0));
+    const TEST_WEIGHT_B: Float32 =
+        Float32::new("constraint", "weight_b", 0.25, Some(0.0), Some(1.0));
+    const TEST_WEIGHT_C: Float32 =
+        Float32::new("constraint", "weight_c", 0.25, Some(0.0), Some(1.0));
+
+    const TEST_FLAG_A: Boolean = Boolean::new("constraint", "flag_a", true);
+    const TEST_FLAG_B: Boolean = Boolean::new("constraint", "flag_b", false);
+
+    #[test]
+    #[serial]
+    fn ordered() {
+        TEST_NEAR.register();
+        TEST_FAR.register();
+        TEST_NEAR.reset();
+        TEST_FAR.reset();
+        add_constraint(Constraint::ordered(&[
+            ("constraint", "near"),
+            ("constraint", "far"),
+        ]));
+
+        assert!(matches!(
+            crate::try_set::<Float32>("constraint", "near", 200.0),
+            Err(SetError::Constraint(_))
+        ));
+        assert_eq!(TEST_NEAR.read(), 0.1);
+
+        TEST_NEAR.write(50.0);
+        assert_eq!(TEST_NEAR.read(), 50.0);
+
+        TEST_FAR.write(10.0);
+        assert_eq!(TEST_FAR.read(), 100.0);
+
+        clear_constraints();
+    }
+
+    #[test]
+    #[serial]
+    fn sum_to_one() {
+        TEST_WEIGHT_A.register();
+        TEST_WEIGHT_B.register();
+        TEST_WEIGHT_C.register();
+        add_constraint(Constraint::sum_to_one(&[
+            ("constraint", "weight_a"),
+            ("constraint", "weight_b"),
+            ("constraint", "weight_c"),
+        ]));
+
+        TEST_WEIGHT_A.write(0.8);
+        assert_eq!(TEST_WEIGHT_A.read(), 0.8);
+        assert!((TEST_WEIGHT_B.read() - 0.1).abs() < 1e-6);
+        assert!((TEST_WEIGHT_C.read() - 0.1).abs() < 1e-6);
+
+        clear_constraints();
+    }
+
+    const TEST_CAPPED_A: Float32 =
+        Float32::new("constraint", "capped_a", 0.4, Some(0.0), Some(1.0));
+    const TEST_CAPPED_B: Float32 =
+        Float32::new("constraint", "capped_b", 0.3, Some(0.0), Some(0.4));
+    const TEST_CAPPED_C: Float32 =
+        Float32::new("constraint", "capped_c", 0.3, Some(0.0), Some(1.0));
+
+    #[test]
+    #[serial]
+    fn rebalance_verified() {
+        for weight in &[TEST_CAPPED_A, TEST_CAPPED_B, TEST_CAPPED_C] {
+            weight.register();
+            weight.reset();
+        }
+        let weights = [
+            ("constraint", "capped_a"),
+            ("constraint", "capped_b"),
+            ("constraint", "capped_c"),
+        ];
+        add_constraint(Constraint::sum_to_one(&weights));
+
+        // capped_b would be rescaled to 0.45, but is clamped to 0.4
+        assert!(matches!(
+            crate::try_set::<Float32>("constraint", "capped_a", 0.1),
+            Err(SetError::Constraint(_))
+        ));
+        assert_eq!(TEST_CAPPED_A.read(), 0.4);
+
+        // Rebalancing keeps capped_b == capped_c, which breaks the order
+        add_constraint(Constraint::ordered(&weights[1..]));
+        assert!(matches!(
+            crate::try_set::<Float32>("constraint", "capped_a", 0.5),
+            Err(SetError::Constraint(_))
+        ));
+        clear_constraints();
+
+        // Validators of the rebalanced variables run too
+        add_constraint(Constraint::sum_to_one(&weights));
+        TEST_CAPPED_C.add_validator(|v| {
+            if v < 0.2 {
+                Err("too small".to_owned())
+            } else {
+                Ok(v)
+            }
+        });
+        assert!(matches!(
+            crate::try_set::<Float32>("constraint", "capped_a", 0.7),
+            Err(SetError::Constraint(_))
+        ));
+        assert_eq!(TEST_CAPPED_C.read(), 0.3);
+
+        crate::clear_validators("constraint", "capped_c");
+        clear_constraints();
+    }
+
+    #[test]
+    #[serial]
+    fn non_numeric() {
+        TEST_WEIGHT_A.register();
+        TEST_FLAG_A.register();
+        TEST_WEIGHT_A.reset();
+        add_constraint(Constraint::sum_to_one(&[
+            ("constraint", "weight_a"),
+            ("constraint", "flag_a"),
+        ]));
+
+        assert_eq!(
+            crate::try_set::<Float32>("constraint", "weight_a", 0.0),
+            Err(SetError::Constraint(
+                "constraint/flag_a is not numeric".to_owned()
+            ))
+        );
+        assert_eq!(TEST_WEIGHT_A.read(), 0.5);
+
+        clear_constraints();
+    }
+
+    #[test]
+    #[serial]
+    fn mutually_exclusive() {
+        TEST_FLAG_A.register();
+        TEST_FLAG_B.register();
+        TEST_FLAG_A.reset();
+        TEST_FLAG_B.reset();
+        add_constraint(Constraint::mutually_exclusive(&[
+            ("constraint", "flag_a"),
+            ("constraint", "flag_b"),
+        ]));
+
+        TEST_FLAG_B.write(true);
+        assert!(!TEST_FLAG_B.read());
+
+        TEST_FLAG_A.write(false);
+        TEST_FLAG_B.write(true);
+        assert!(TEST_FLAG_B.read());
+
+        clear_constraints();
+    }
+}
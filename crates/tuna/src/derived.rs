@@ -0,0 +1,491 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Read-only variables computed from other variables with an expression, and
+recomputed whenever one of their inputs changes.
+*/
+
+use crate::{
+    compat::{prelude::*, Lazy, RwLock},
+    expr::Expr,
+    AsTuneable, TunaState, Tuneable, TUNA_STATE,
+};
+#[cfg(feature = "std")]
+use nanoserde::{DeJson, SerJson};
+
+/// The definition of a derived variable
+#[derive(Copy, Clone, Debug)]
+pub struct Derived {
+    pub(crate) category: &'static str,
+    pub(crate) name: &'static str,
+    pub(crate) expression: &'static str,
+    pub(crate) description: Option<&'static str>,
+}
+
+/// The state of a derived variable
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DerivedVariable {
+    pub(crate) expression: String,
+    pub(crate) current: f64,
+    /// Set when the expression could not be evaluated, e.g. because an input
+    /// isn't registered yet, and empty otherwise. Not an `Option`, as nanoserde
+    /// leaves a trailing comma when the last field is `None`.
+    pub(crate) error: String,
+}
+
+impl DerivedVariable {
+    /// The expression the value is computed from
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The most recently computed value
+    pub fn current(&self) -> f64 {
+        self.current
+    }
+
+    /// Why the expression couldn't be evaluated, if it couldn't
+    pub fn error(&self) -> Option<&str> {
+        if self.error.is_empty() {
+            None
+        } else {
+            Some(&self.error)
+        }
+    }
+}
+
+struct Entry {
+    category: String,
+    name: String,
+    expression: String,
+    expr: Expr,
+    dependencies: Vec<(String, String)>,
+}
+
+static DERIVED: Lazy<RwLock<Vec<Entry>>> = Lazy::new(|| RwLock::new(Default::default()));
+
+impl Derived {
+    /// Define a new derived variable that can be registered with tuna. The
+    /// expression refers to other variables as `category.name`.
+    pub const fn new(category: &'static str, name: &'static str, expression: &'static str) -> Self {
+        Self {
+            category,
+            name,
+            expression,
+            description: None,
+        }
+    }
+
+    /// Attach a description, shown by tools and exported in the schema
+    pub const fn with_description(self, description: &'static str) -> Self {
+        Self {
+            description: Some(description),
+            ..self
+        }
+    }
+
+    /// Explicitly register the derived variable with tuna. Errors in the
+    /// expression are logged.
+    pub fn register(&self) {
+        if let Err(e) = register_derived(self.category, self.name, self.expression) {
+            log::error!(
+                "Failed registering derived variable {}/{}: {}",
+                self.category,
+                self.name,
+                e
+            );
+        } else if let Some(description) = self.description {
+            crate::set_description(self.category, self.name, description);
+        }
+    }
+
+    /// Read the computed value. This will automatically call register on a
+    /// lookup miss. If the name is registered as a regular variable, zero is
+    /// returned without registering.
+    pub fn read(&self) -> f64 {
+        crate::get::<Derived>(self.category, self.name).unwrap_or_else(|| {
+            if crate::is_registered(self.category, self.name) {
+                return 0.0;
+            }
+
+            self.register();
+            crate::get::<Derived>(self.category, self.name).unwrap_or_default()
+        })
+    }
+
+    /// Read the computed value from a snapshot, or zero if it wasn't
+    /// registered when the snapshot was taken.
+    pub fn read_in(&self, snapshot: &crate::Snapshot) -> f64 {
+        snapshot
+            .get::<Derived>(self.category, self.name)
+            .unwrap_or_default()
+    }
+}
+
+impl AsTuneable for Derived {
+    type Result = f64;
+
+    fn make_tuneable(&self) -> Tuneable {
+        Tuneable::Derived(DerivedVariable {
+            expression: self.expression.to_owned(),
+            current: 0.0,
+            error: String::new(),
+        })
+    }
+
+    fn update(_tuneable: &mut Tuneable, _var: f64) -> bool {
+        false
+    }
+
+    fn reset(_tuneable: &mut Tuneable) {}
+
+    fn from_tuneable(v: &Tuneable) -> Option<f64> {
+        match v {
+            Tuneable::Derived(x) => Some(x.current),
+            _ => None,
+        }
+    }
+}
+
+/// Register a derived variable computed from `expression`. Registering the
+/// same expression again does nothing. Fails if the expression doesn't parse,
+/// if it would make derived variables depend on each other in a cycle, or if
+/// the name is already taken by a different expression or a regular variable.
+pub fn register_derived(category: &str, name: &str, expression: &str) -> Result<(), String> {
+    let expr = Expr::parse(expression)?;
+    let dependencies = expr.dependencies();
+
+    if let Some(existing) = TUNA_STATE
+        .read()
+        .get(category)
+        .and_then(|group| group.get(name))
+    {
+        if !matches!(existing, Tuneable::Derived(_)) {
+            return Err(format!(
+                "{}/{} is already registered as {}",
+                category,
+                name,
+                existing.kind().as_str()
+            ));
+        }
+    }
+
+    {
+        let mut derived = DERIVED.write();
+        if let Some(entry) = derived
+            .iter()
+            .find(|e| e.category == category && e.name == name)
+        {
+            return if entry.expression == expression {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{}/{} is already derived from `{}`",
+                    category, name, entry.expression
+                ))
+            };
+        }
+
+        // Walk the inputs, following other derived variables, to see if any
+        // path leads back here.
+        let mut stack = dependencies.clone();
+        let mut visited: Vec<(String, String)> = vec![];
+        while let Some((c, n)) = stack.pop() {
+            if c == category && n == name {
+                return Err(format!(
+                    "`{}` creates a dependency cycle through {}.{}",
+                    expression, category, name
+                ));
+            }
+
+            if visited.contains(&(c.clone(), n.clone())) {
+                continue;
+            }
+
+            if let Some(entry) = derived.iter().find(|e| e.category == c && e.name == n) {
+                stack.extend(entry.dependencies.iter().cloned());
+            }
+            visited.push((c, n));
+        }
+
+        derived.push(Entry {
+            category: category.to_owned(),
+            name: name.to_owned(),
+            expression: expression.to_owned(),
+            expr,
+            dependencies,
+        });
+    }
+
+    log::debug!(
+        "Registering derived variable: {}/{} = {}",
+        category,
+        name,
+        expression
+    );
+
+    let mut tuna = TUNA_STATE.write();
+    tuna.entry(category.to_owned())
+        .or_default()
+        .entry(name.to_owned())
+        .or_insert_with(|| {
+            Tuneable::Derived(DerivedVariable {
+                expression: expression.to_owned(),
+                current: 0.0,
+                error: String::new(),
+            })
+        });
+
+    let changed = [(category.to_owned(), name.to_owned())];
+    recompute(&mut tuna, &changed);
+    crate::snapshot::bump_epoch(&changed);
+    Ok(())
+}
+
+/// Drop the expression of a derived variable that was replaced by a regular
+/// one
+pub(crate) fn forget(category: &str, name: &str) {
+    DERIVED
+        .write()
+        .retain(|e| !(e.category == category && e.name == name));
+}
+
+/// Recompute all derived variables that depend on any of the changed
+/// variables, directly or through other derived variables. Each is evaluated
+/// once, after the derived variables it depends on.
+pub(crate) fn recompute(state: &mut TunaState, changed: &[(String, String)]) {
+    let derived = DERIVED.read();
+    if derived.is_empty() {
+        return;
+    }
+
+    let is = |entry: &Entry, category: &str, name: &str| {
+        entry.category == category && entry.name == name
+    };
+
+    // Collect the changed derived variables and everything downstream
+    let mut affected = vec![false; derived.len()];
+    let mut queue = changed.to_vec();
+    while let Some((category, name)) = queue.pop() {
+        for (i, entry) in derived.iter().enumerate() {
+            if !affected[i]
+                && (is(entry, &category, &name)
+                    || entry
+                        .dependencies
+                        .iter()
+                        .any(|(c, n)| *c == category && *n == name))
+            {
+                affected[i] = true;
+                queue.push((entry.category.clone(), entry.name.clone()));
+            }
+        }
+    }
+
+    // Registration rejects cycles, so some variable is always ready
+    let mut pending = (0..derived.len())
+        .filter(|i| affected[*i])
+        .collect::<Vec<_>>();
+    let mut recomputed = vec![];
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<usize>, Vec<usize>) = pending.iter().partition(|&&i| {
+            !derived[i]
+                .dependencies
+                .iter()
+                .any(|(c, n)| pending.iter().any(|&j| i != j && is(&derived[j], c, n)))
+        });
+        if ready.is_empty() {
+            break;
+        }
+
+        for i in ready {
+            let entry = &derived[i];
+            let result = entry
+                .expr
+                .eval(&|c, n| {
+                    state
+                        .get(c)
+                        .and_then(|group| group.get(n))
+                        .and_then(Tuneable::as_f64)
+                })
+                .and_then(|v| {
+                    if v.is_finite() {
+                        Ok(v)
+                    } else {
+                        Err(format!("evaluated to {}", v))
+                    }
+                });
+
+            if let Some(Tuneable::Derived(var)) = state
+                .get_mut(&entry.category)
+                .and_then(|group| group.get_mut(&entry.name))
+            {
+                match result {
+                    Ok(v) => {
+                        var.current = v;
+                        var.error.clear();
+                    }
+                    Err(e) => var.error = e,
+                }
+                recomputed.push((entry.category.clone(), entry.name.clone()));
+            }
+        }
+        pending = waiting;
+    }
+
+    if !recomputed.is_empty() {
+        crate::snapshot::bump_epoch(&recomputed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register_derived, Derived};
+    use crate::compat::prelude::*;
+    use crate::{Float32, Int32, SetError};
+    use serial_test::serial;
+
+    const TEST_DT: Float32 = Float32::new("derived", "dt", 0.02, Some(0.0), None);
+    const TEST_SUBSTEPS: Int32 = Int32::new("derived", "substeps", 4, Some(1), None);
+    const TEST_SUBSTEP_DT: Derived =
+        Derived::new("derived", "substep_dt", "derived.dt / derived.substeps");
+    const TEST_SUBSTEP_DT_MS: Derived =
+        Derived::new("derived", "substep_dt_ms", "derived.substep_dt * 1000");
+
+    #[test]
+    #[serial]
+    fn recompute() {
+        TEST_DT.register();
+        TEST_SUBSTEPS.register();
+        TEST_SUBSTEP_DT.register();
+        TEST_SUBSTEP_DT_MS.register();
+        TEST_DT.reset();
+        TEST_SUBSTEPS.reset();
+
+        assert!((TEST_SUBSTEP_DT.read() - 0.005).abs() < 1e-6);
+
+        TEST_SUBSTEPS.write(10);
+        assert!((TEST_SUBSTEP_DT.read() - 0.002).abs() < 1e-6);
+        assert!((TEST_SUBSTEP_DT_MS.read() - 2.0).abs() < 1e-3);
+
+        TEST_DT.reset();
+        TEST_SUBSTEPS.reset();
+        assert!((TEST_SUBSTEP_DT_MS.read() - 5.0).abs() < 1e-3);
+    }
+
+    const TEST_BASE: Float32 = Float32::new("derived", "base", 1.0, None, None);
+    const TEST_DOUBLE: Derived = Derived::new("derived", "double", "derived.base * 2");
+    const TEST_NEXT: Derived = Derived::new("derived", "next", "derived.double + 1");
+    const TEST_TOTAL: Derived = Derived::new(
+        "derived",
+        "total",
+        "derived.next + derived.double + derived.base",
+    );
+
+    #[test]
+    #[serial]
+    fn dependency_order() {
+        TEST_BASE.register();
+        TEST_BASE.reset();
+        TEST_DOUBLE.register();
+        TEST_NEXT.register();
+        TEST_TOTAL.register();
+        assert_eq!(TEST_TOTAL.read(), 3.0 + 2.0 + 1.0);
+
+        TEST_BASE.write(2.0);
+        assert_eq!(TEST_DOUBLE.read(), 4.0);
+        assert_eq!(TEST_NEXT.read(), 5.0);
+        assert_eq!(TEST_TOTAL.read(), 5.0 + 4.0 + 2.0);
+        TEST_BASE.reset();
+    }
+
+    #[test]
+    #[serial]
+    fn read_only() {
+        TEST_SUBSTEP_DT.register();
+
+        assert_eq!(
+            crate::try_set::<Derived>("derived", "substep_dt", 1.0),
+            Err(SetError::ReadOnly)
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "std")]
+    fn serialize() {
+        use nanoserde::{DeJson, SerJson};
+
+        TEST_DT.register();
+        TEST_SUBSTEPS.register();
+        TEST_SUBSTEP_DT.register();
+        register_derived("derived", "missing", "derived.not_registered * 2").unwrap();
+
+        for (name, failed) in &[("substep_dt", false), ("missing", true)] {
+            let state = crate::describe("derived", name).unwrap().state;
+            let json = SerJson::serialize_json(&state);
+            assert!(
+                serde_json::from_str::<serde_json::Value>(&json).is_ok(),
+                "{}",
+                json
+            );
+
+            let back: crate::Tuneable = DeJson::deserialize_json(&json).unwrap();
+            match (state, back) {
+                (crate::Tuneable::Derived(state), crate::Tuneable::Derived(back)) => {
+                    assert_eq!(state.error().is_some(), *failed);
+                    assert_eq!(state.error(), back.error());
+                    assert_eq!(state.current(), back.current());
+                }
+                _ => panic!("not derived"),
+            }
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn cycle() {
+        assert!(register_derived("derived", "cycle_a", "derived.cycle_b + 1").is_ok());
+        assert!(register_derived("derived", "cycle_b", "derived.cycle_a + 1").is_err());
+        assert!(register_derived("derived", "cycle_c", "derived.cycle_c").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn redefined() {
+        TEST_DT.register();
+        TEST_SUBSTEP_DT.register();
+
+        assert!(register_derived("derived", "substep_dt", "derived.dt / derived.substeps").is_ok());
+        assert!(register_derived("derived", "substep_dt", "derived.dt").is_err());
+        assert!(register_derived("derived", "dt", "derived.substeps * 2").is_err());
+        assert_eq!(TEST_DT.read(), 0.02);
+    }
+
+    #[test]
+    #[serial]
+    fn not_finite() {
+        const TEST_ZERO: Float32 = Float32::new("derived", "zero", 0.0, None, None);
+        const TEST_INVERSE: Derived = Derived::new("derived", "inverse", "1 / derived.zero");
+        const TEST_ROOT: Derived = Derived::new("derived", "sqrt", "sqrt(derived.zero - 1)");
+
+        TEST_ZERO.register();
+        TEST_ZERO.reset();
+        TEST_INVERSE.register();
+        TEST_ROOT.register();
+
+        let error = |name| match crate::describe("derived", name).unwrap().state {
+            crate::Tuneable::Derived(var) => var.error().map(str::to_owned),
+            _ => None,
+        };
+        assert_eq!(error("inverse").as_deref(), Some("division by zero"));
+        assert_eq!(error("sqrt").as_deref(), Some("evaluated to NaN"));
+
+        TEST_ZERO.write(4.0);
+        assert_eq!(error("inverse"), None);
+        assert_eq!(TEST_INVERSE.read(), 0.25);
+    }
+}
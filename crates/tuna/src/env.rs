@@ -0,0 +1,142 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Overrides from environment variables, e.g. for configuring CI runs.
+*/
+
+use crate::{api::try_set_str, ChangeSource, SetError, TUNA_STATE};
+
+/// The outcome of [`apply_env`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnvReport {
+    /// The variables that were set, as category and name
+    pub applied: Vec<(String, String)>,
+    /// Environment variables with the prefix that don't name a registered
+    /// tuneable
+    pub unknown: Vec<String>,
+    /// Environment variables whose value couldn't be parsed or was refused
+    pub failed: Vec<(String, SetError)>,
+}
+
+/// Find the registered category and name matching the parts of an environment
+/// variable, ignoring case.
+fn resolve(category: &str, name: &str) -> Option<(String, String)> {
+    let tuna = TUNA_STATE.read();
+    let (category, group) = tuna
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(category))?;
+    let name = group.keys().find(|n| n.eq_ignore_ascii_case(name))?;
+
+    Some((category.clone(), name.clone()))
+}
+
+/// Apply overrides from environment variables named
+/// `<prefix>__<category>__<name>`, e.g. `TUNA__RENDER__SHADOWS=false` for the
+/// prefix `TUNA`. Categories and names are matched ignoring case, and values
+/// are parsed according to the type the variable was registered with, so call
+/// this after registering.
+pub fn apply_env(prefix: &str) -> EnvReport {
+    let mut report = EnvReport::default();
+    let prefix = format!("{}__", prefix);
+
+    for (key, value) in std::env::vars_os() {
+        let key = match key.into_string() {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+
+        let path = match key.strip_prefix(&prefix) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let (category, name) = match path.split_once("__").and_then(|(c, n)| resolve(c, n)) {
+            Some(found) => found,
+            None => {
+                log::warn!("Unknown tuneable in environment: {}", key);
+                report.unknown.push(key);
+                continue;
+            }
+        };
+
+        let result = match value.into_string() {
+            Ok(value) => try_set_str(&category, &name, &value, ChangeSource::Env),
+            Err(value) => Err(SetError::Parse(format!("{:?} is not unicode", value))),
+        };
+
+        match result {
+            Ok(()) => {
+                log::debug!("Applied {} from environment", key);
+                report.applied.push((category, name));
+            }
+            Err(e) => {
+                log::warn!("Failed applying {} from environment: {}", key, e);
+                report.failed.push((key, e));
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_env;
+    use crate::{Boolean, Float32, SetError};
+    use serial_test::serial;
+
+    const TEST_SHADOWS: Boolean = Boolean::new("env_render", "shadows", true);
+    const TEST_GRAVITY: Float32 = Float32::new("env_physics", "gravity", -9.81, None, None);
+
+    #[test]
+    #[serial]
+    fn overrides() {
+        TEST_SHADOWS.register();
+        TEST_GRAVITY.register();
+        TEST_SHADOWS.reset();
+        TEST_GRAVITY.reset();
+
+        std::env::set_var("TUNATEST__ENV_RENDER__SHADOWS", "false");
+        std::env::set_var("TUNATEST__env_physics__GRAVITY", "-4.5");
+        std::env::set_var("TUNATEST__ENV_RENDER__MISSING", "1");
+        std::env::set_var("TUNATEST__NO_SEPARATOR", "1");
+
+        let mut report = apply_env("TUNATEST");
+        report.applied.sort();
+        report.unknown.sort();
+
+        assert!(!TEST_SHADOWS.read());
+        assert_eq!(TEST_GRAVITY.read(), -4.5);
+        assert_eq!(
+            report.applied,
+            vec![
+                ("env_physics".to_owned(), "gravity".to_owned()),
+                ("env_render".to_owned(), "shadows".to_owned()),
+            ]
+        );
+        assert_eq!(
+            report.unknown,
+            vec!["TUNATEST__ENV_RENDER__MISSING", "TUNATEST__NO_SEPARATOR"]
+        );
+        assert!(report.failed.is_empty());
+
+        std::env::set_var("TUNATEST__ENV_PHYSICS__GRAVITY", "down");
+        let report = apply_env("TUNATEST");
+        assert!(matches!(
+            report.failed.as_slice(),
+            [(key, SetError::Parse(_))] if key == "TUNATEST__ENV_PHYSICS__GRAVITY"
+        ));
+
+        for key in [
+            "TUNATEST__ENV_RENDER__SHADOWS",
+            "TUNATEST__env_physics__GRAVITY",
+            "TUNATEST__ENV_RENDER__MISSING",
+            "TUNATEST__NO_SEPARATOR",
+            "TUNATEST__ENV_PHYSICS__GRAVITY",
+        ] {
+            std::env::remove_var(key);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+A small arithmetic expression language used for derived variables.
+
+Expressions support numbers, variables written as `category.name`, the
+operators `+ - * / % ^`, parentheses, and the functions `abs`, `sqrt`,
+`floor`, `ceil`, `min` and `max`. Everything evaluates to `f64`; booleans read
+as `1.0` or `0.0`.
+*/
+
+use core::{iter::Peekable, str::Chars};
+
+use crate::compat::{math, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+/// A parsed expression
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Expr {
+    Number(f64),
+    Variable(String, String),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(Op),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars: Peekable<Chars> = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                let value = number
+                    .parse()
+                    .map_err(|_| format!("invalid number `{}`", number))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => {
+                chars.next();
+                tokens.push(match c {
+                    '+' => Token::Op(Op::Add),
+                    '-' => Token::Op(Op::Sub),
+                    '*' => Token::Op(Op::Mul),
+                    '/' => Token::Op(Op::Div),
+                    '%' => Token::Op(Op::Rem),
+                    '^' => Token::Op(Op::Pow),
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    ',' => Token::Comma,
+                    _ => return Err(format!("unexpected character `{}`", c)),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected {:?}, found {:?}", expected, token)),
+            None => Err(format!("expected {:?}, found end of expression", expected)),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+
+        while let Some(Token::Op(op @ (Op::Add | Op::Sub))) = self.peek().cloned() {
+            self.next();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+
+        while let Some(Token::Op(op @ (Op::Mul | Op::Div | Op::Rem))) = self.peek().cloned() {
+            self.next();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if let Some(Token::Op(Op::Sub)) = self.peek() {
+            self.next();
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+
+        let base = self.atom()?;
+        if let Some(Token::Op(Op::Pow)) = self.peek() {
+            self.next();
+            return Ok(Expr::Binary(
+                Op::Pow,
+                Box::new(base),
+                Box::new(self.unary()?),
+            ));
+        }
+
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(v)) => Ok(Expr::Number(v)),
+            Some(Token::Open) => {
+                let inner = self.expression()?;
+                self.expect(Token::Close)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(ident)) => {
+                if let Some(Token::Open) = self.peek() {
+                    self.next();
+                    let mut args = vec![];
+                    if let Some(Token::Close) = self.peek() {
+                        self.next();
+                    } else {
+                        loop {
+                            args.push(self.expression()?);
+                            match self.next() {
+                                Some(Token::Comma) => continue,
+                                Some(Token::Close) => break,
+                                _ => return Err(format!("unterminated call to `{}`", ident)),
+                            }
+                        }
+                    }
+
+                    check_arity(&ident, args.len())?;
+                    return Ok(Expr::Call(ident, args));
+                }
+
+                match ident.split_once('.') {
+                    Some((category, name)) if !category.is_empty() && !name.is_empty() => {
+                        Ok(Expr::Variable(category.to_owned(), name.to_owned()))
+                    }
+                    _ => Err(format!(
+                        "`{}` is not a variable, expected `category.name`",
+                        ident
+                    )),
+                }
+            }
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_owned()),
+        }
+    }
+}
+
+fn check_arity(function: &str, count: usize) -> Result<(), String> {
+    let expected = match function {
+        "abs" | "sqrt" | "floor" | "ceil" => 1,
+        "min" | "max" => 2,
+        _ => return Err(format!("unknown function `{}`", function)),
+    };
+
+    if count == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{}` takes {} arguments, got {}",
+            function, expected, count
+        ))
+    }
+}
+
+impl Expr {
+    /// Parse an expression from a string
+    pub(crate) fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+
+        let expr = parser.expression()?;
+        match parser.next() {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {:?} after expression", token)),
+        }
+    }
+
+    /// All variables referenced by the expression, as `(category, name)`
+    pub(crate) fn dependencies(&self) -> Vec<(String, String)> {
+        let mut out = vec![];
+        self.collect_dependencies(&mut out);
+        out
+    }
+
+    fn collect_dependencies(&self, out: &mut Vec<(String, String)>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Variable(category, name) => {
+                let dep = (category.clone(), name.clone());
+                if !out.contains(&dep) {
+                    out.push(dep);
+                }
+            }
+            Expr::Neg(inner) => inner.collect_dependencies(out),
+            Expr::Binary(_, lhs, rhs) => {
+                lhs.collect_dependencies(out);
+                rhs.collect_dependencies(out);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|a| a.collect_dependencies(out)),
+        }
+    }
+
+    /// Evaluate the expression, looking up variables with `lookup`
+    pub(crate) fn eval(&self, lookup: &impl Fn(&str, &str) -> Option<f64>) -> Result<f64, String> {
+        Ok(match self {
+            Expr::Number(v) => *v,
+            Expr::Variable(category, name) => lookup(category, name)
+                .ok_or_else(|| format!("unknown variable `{}.{}`", category, name))?,
+            Expr::Neg(inner) => -inner.eval(lookup)?,
+            Expr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(lookup)?, rhs.eval(lookup)?);
+                match op {
+                    Op::Add => lhs + rhs,
+                    Op::Sub => lhs - rhs,
+                    Op::Mul => lhs * rhs,
+                    Op::Div | Op::Rem if rhs == 0.0 => return Err("division by zero".to_owned()),
+                    Op::Div => lhs / rhs,
+                    Op::Rem => lhs % rhs,
+                    Op::Pow => math::pow(lhs, rhs),
+                }
+            }
+            Expr::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|a| a.eval(lookup))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match function.as_str() {
+                    "abs" => args[0].abs(),
+                    "sqrt" => math::sqrt(args[0]),
+                    "floor" => math::floor(args[0]),
+                    "ceil" => math::ceil(args[0]),
+                    "min" => args[0].min(args[1]),
+                    "max" => args[0].max(args[1]),
+                    _ => unreachable!("checked when parsing"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expr;
+    use crate::compat::prelude::*;
+
+    fn lookup(category: &str, name: &str) -> Option<f64> {
+        match (category, name) {
+            ("physics", "dt") => Some(0.02),
+            ("physics", "substeps") => Some(4.0),
+            _ => None,
+        }
+    }
+
+    fn eval(source: &str) -> Result<f64, String> {
+        Expr::parse(source)?.eval(&lookup)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3"), Ok(7.0));
+        assert_eq!(eval("(1 + 2) * 3"), Ok(9.0));
+        assert_eq!(eval("-2 ^ 2"), Ok(-4.0));
+        assert_eq!(eval("2 ^ 3 ^ 2"), Ok(512.0));
+        assert_eq!(eval("max(1, min(5, 3)) - abs(-1)"), Ok(2.0));
+    }
+
+    #[test]
+    fn variables() {
+        assert_eq!(eval("physics.dt / physics.substeps"), Ok(0.005));
+        assert_eq!(
+            Expr::parse("physics.dt / physics.substeps + physics.dt")
+                .unwrap()
+                .dependencies(),
+            vec![
+                ("physics".to_owned(), "dt".to_owned()),
+                ("physics".to_owned(), "substeps".to_owned())
+            ]
+        );
+        assert!(eval("physics.missing").is_err());
+        assert_eq!(
+            eval("physics.dt / (physics.substeps - 4)"),
+            Err("division by zero".to_owned())
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Expr::parse("1 +").is_err());
+        assert!(Expr::parse("(1").is_err());
+        assert!(Expr::parse("dt * 2").is_err());
+        assert!(Expr::parse("foo(1)").is_err());
+        assert!(Expr::parse("min(1)").is_err());
+        assert!(Expr::parse("1 $ 2").is_err());
+    }
+}
@@ -6,8 +6,11 @@
 
 */
 
+#[cfg(feature = "std")]
 use nanoserde::{DeJson, SerJson};
 
+use crate::RangeMode;
+
 /// The definition of a float variable
 #[derive(Copy, Clone, Debug)]
 pub struct Float32 {
@@ -17,15 +20,32 @@ pub struct Float32 {
 
     pub(crate) min: Option<f32>,
     pub(crate) max: Option<f32>,
+    pub(crate) soft_min: Option<f32>,
+    pub(crate) soft_max: Option<f32>,
+    pub(crate) mode: RangeMode,
+    pub(crate) description: Option<&'static str>,
 }
 
 /// The state of a float variable
-#[derive(Clone, Debug, SerJson, DeJson)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Float32Variable {
     pub(crate) default: f32,
 
     pub(crate) min: Option<f32>,
     pub(crate) max: Option<f32>,
+    /// The range suggested to tools such as sliders, which doesn't limit
+    /// writes
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_min: Option<f32>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_max: Option<f32>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) mode: RangeMode,
     pub(crate) current: f32,
 }
 
@@ -38,15 +58,32 @@ pub struct Float64 {
 
     pub(crate) min: Option<f64>,
     pub(crate) max: Option<f64>,
+    pub(crate) soft_min: Option<f64>,
+    pub(crate) soft_max: Option<f64>,
+    pub(crate) mode: RangeMode,
+    pub(crate) description: Option<&'static str>,
 }
 
 /// The state of a float variable
-#[derive(Clone, Debug, SerJson, DeJson)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Float64Variable {
     pub(crate) default: f64,
 
     pub(crate) min: Option<f64>,
     pub(crate) max: Option<f64>,
+    /// The range suggested to tools such as sliders, which doesn't limit
+    /// writes
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_min: Option<f64>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_max: Option<f64>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) mode: RangeMode,
     pub(crate) current: f64,
 }
 
@@ -6,8 +6,11 @@
 
 */
 
+#[cfg(feature = "std")]
 use nanoserde::{DeJson, SerJson};
 
+use crate::RangeMode;
+
 /// The definition of a float variable
 #[derive(Copy, Clone, Debug)]
 pub struct Int32 {
@@ -17,15 +20,32 @@ pub struct Int32 {
 
     pub(crate) min: Option<i32>,
     pub(crate) max: Option<i32>,
+    pub(crate) soft_min: Option<i32>,
+    pub(crate) soft_max: Option<i32>,
+    pub(crate) mode: RangeMode,
+    pub(crate) description: Option<&'static str>,
 }
 
 /// The state of a float variable
-#[derive(Clone, Debug, SerJson, DeJson)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int32Variable {
     pub(crate) default: i32,
 
     pub(crate) min: Option<i32>,
     pub(crate) max: Option<i32>,
+    /// The range suggested to tools such as sliders, which doesn't limit
+    /// writes
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_min: Option<i32>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_max: Option<i32>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) mode: RangeMode,
     pub(crate) current: i32,
 }
 
@@ -38,15 +58,32 @@ pub struct Int64 {
 
     pub(crate) min: Option<i64>,
     pub(crate) max: Option<i64>,
+    pub(crate) soft_min: Option<i64>,
+    pub(crate) soft_max: Option<i64>,
+    pub(crate) mode: RangeMode,
+    pub(crate) description: Option<&'static str>,
 }
 
 /// The state of a float variable
-#[derive(Clone, Debug, SerJson, DeJson)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int64Variable {
     pub(crate) default: i64,
 
     pub(crate) min: Option<i64>,
     pub(crate) max: Option<i64>,
+    /// The range suggested to tools such as sliders, which doesn't limit
+    /// writes
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_min: Option<i64>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) soft_max: Option<i64>,
+    #[cfg_attr(feature = "std", nserde(default))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) mode: RangeMode,
     pub(crate) current: i64,
 }
 
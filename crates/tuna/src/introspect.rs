@@ -0,0 +1,235 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Read-only listing of the registered variables, for building tools and UIs.
+*/
+
+use crate::{
+    compat::{prelude::*, Lazy, Map, RwLock},
+    Tuneable, TUNA_STATE,
+};
+
+static DESCRIPTIONS: Lazy<RwLock<Map<String, Map<String, String>>>> =
+    Lazy::new(|| RwLock::new(Default::default()));
+
+/// The type of a variable
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Float32,
+    Float64,
+    Int32,
+    Int64,
+    Uint,
+    Size,
+    Boolean,
+    Derived,
+}
+
+impl Kind {
+    /// A short name for the type: `f32`, `f64`, `i32`, `i64`, `u32`, `usize`,
+    /// `bool` or `derived`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Kind::Float32 => "f32",
+            Kind::Float64 => "f64",
+            Kind::Int32 => "i32",
+            Kind::Int64 => "i64",
+            Kind::Uint => "u32",
+            Kind::Size => "usize",
+            Kind::Boolean => "bool",
+            Kind::Derived => "derived",
+        }
+    }
+}
+
+/// Everything known about a single variable at the time it was described
+#[derive(Clone, Debug)]
+pub struct Descriptor {
+    pub category: String,
+    pub name: String,
+    pub kind: Kind,
+    /// The current value as a number. Booleans are `1.0` or `0.0`.
+    pub current: Option<f64>,
+    /// The default value as a number; derived variables have none
+    pub default: Option<f64>,
+    /// The limits writes are clamped or wrapped to
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// The range suggested to tools, which may be narrower than the limits
+    pub soft_min: Option<f64>,
+    pub soft_max: Option<f64>,
+    /// What the variable is for, if documented
+    pub description: Option<String>,
+    /// The full state, for reading values in their own type
+    pub state: Tuneable,
+    /// What last wrote the variable, if it was written since being registered
+    pub last_source: Option<crate::ChangeSource>,
+    /// How often the variable has been read and written
+    #[cfg(feature = "stats")]
+    pub stats: crate::Stats,
+}
+
+/// The default and `[min, max, soft_min, soft_max]` of a variable as numbers
+pub(crate) fn definition(state: &Tuneable) -> (Option<f64>, [Option<f64>; 4]) {
+    match state {
+        Tuneable::Float32(v) => (
+            Some(v.default as f64),
+            [v.min, v.max, v.soft_min, v.soft_max].map(|v| v.map(f64::from)),
+        ),
+        Tuneable::Float64(v) => (Some(v.default), [v.min, v.max, v.soft_min, v.soft_max]),
+        Tuneable::Int32(v) => (
+            Some(v.default as f64),
+            [v.min, v.max, v.soft_min, v.soft_max].map(|v| v.map(f64::from)),
+        ),
+        Tuneable::Int64(v) => (
+            Some(v.default as f64),
+            [v.min, v.max, v.soft_min, v.soft_max].map(|v| v.map(|v| v as f64)),
+        ),
+        Tuneable::Boolean(v) => (Some(if v.default { 1.0 } else { 0.0 }), [None; 4]),
+        Tuneable::Uint(_) | Tuneable::Size(_) | Tuneable::Derived(_) => (None, [None; 4]),
+    }
+}
+
+impl Descriptor {
+    fn new(category: &str, name: &str, state: &Tuneable) -> Self {
+        let (default, [min, max, soft_min, soft_max]) = definition(state);
+
+        Self {
+            category: category.to_owned(),
+            name: name.to_owned(),
+            kind: state.kind(),
+            current: state.as_f64(),
+            default,
+            min,
+            max,
+            soft_min,
+            soft_max,
+            description: DESCRIPTIONS
+                .read()
+                .get(category)
+                .and_then(|group| group.get(name))
+                .cloned(),
+            state: state.clone(),
+            last_source: crate::last_source(category, name),
+            #[cfg(feature = "stats")]
+            stats: crate::stats::stats(category, name),
+        }
+    }
+}
+
+/// Set the description of a variable, replacing any earlier one. Definitions
+/// made with `with_description` do this when registered.
+pub fn set_description(category: &str, name: &str, description: &str) {
+    DESCRIPTIONS
+        .write()
+        .entry(category.to_owned())
+        .or_default()
+        .insert(name.to_owned(), description.to_owned());
+}
+
+/// The names of all categories, sorted
+pub fn categories() -> Vec<String> {
+    let mut categories = TUNA_STATE.read().keys().cloned().collect::<Vec<_>>();
+    categories.sort();
+    categories
+}
+
+/// Describe all variables in a category, sorted by name. Empty if the
+/// category doesn't exist.
+pub fn variables(category: &str) -> Vec<Descriptor> {
+    let tuna = TUNA_STATE.read();
+    let mut variables = tuna.get(category).map_or_else(Vec::new, |group| {
+        group
+            .iter()
+            .map(|(name, state)| Descriptor::new(category, name, state))
+            .collect()
+    });
+
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+    variables
+}
+
+/// Describe every registered variable, sorted by category and then name. The
+/// state is locked once, so unlike calling [`variables`] for each of the
+/// [`categories`] the result is consistent.
+pub fn all() -> Vec<Descriptor> {
+    let tuna = TUNA_STATE.read();
+    let mut variables = tuna
+        .iter()
+        .flat_map(|(category, group)| {
+            group
+                .iter()
+                .map(move |(name, state)| Descriptor::new(category, name, state))
+        })
+        .collect::<Vec<_>>();
+    drop(tuna);
+
+    variables.sort_by(|a, b| (&a.category, &a.name).cmp(&(&b.category, &b.name)));
+    variables
+}
+
+/// Describe a single variable, if it is registered
+pub fn describe(category: &str, name: &str) -> Option<Descriptor> {
+    TUNA_STATE
+        .read()
+        .get(category)
+        .and_then(|group| group.get(name))
+        .map(|state| Descriptor::new(category, name, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{all, categories, describe, variables, Kind};
+    use crate::compat::prelude::*;
+    use crate::{Boolean, Int32};
+    use serial_test::serial;
+
+    const TEST_COUNT: Int32 = Int32::new("introspect", "count", 3, Some(0), Some(10))
+        .with_soft_range(Some(1), Some(5))
+        .with_description("How many");
+    const TEST_ENABLE: Boolean = Boolean::new("introspect", "enable", true);
+
+    #[test]
+    #[serial]
+    fn listing() {
+        TEST_COUNT.register();
+        TEST_ENABLE.register();
+        // The soft range doesn't limit writes, the hard one does
+        TEST_COUNT.write(8);
+        assert_eq!(TEST_COUNT.read(), 8);
+        TEST_COUNT.write(12);
+        assert_eq!(TEST_COUNT.read(), 10);
+        TEST_COUNT.write(5);
+
+        assert!(categories().contains(&"introspect".to_owned()));
+
+        let vars = variables("introspect");
+        let names = vars.iter().map(|d| d.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["count", "enable"]);
+
+        let everything = all();
+        assert!(everything
+            .windows(2)
+            .all(|pair| (&pair[0].category, &pair[0].name) < (&pair[1].category, &pair[1].name)));
+        let names = everything
+            .iter()
+            .filter(|d| d.category == "introspect")
+            .map(|d| d.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["count", "enable"]);
+
+        let count = describe("introspect", "count").unwrap();
+        assert_eq!(count.kind, Kind::Int32);
+        assert_eq!(count.kind.as_str(), "i32");
+        assert_eq!(count.current, Some(5.0));
+        assert_eq!(count.default, Some(3.0));
+        assert_eq!((count.min, count.max), (Some(0.0), Some(10.0)));
+        assert_eq!((count.soft_min, count.soft_max), (Some(1.0), Some(5.0)));
+        assert_eq!(count.description.as_deref(), Some("How many"));
+
+        assert!(describe("introspect", "missing").is_none());
+        assert!(variables("missing").is_empty());
+    }
+}
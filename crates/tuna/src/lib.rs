@@ -1,24 +1,61 @@
-#[doc = include_str!("../README.md")]
-use parking_lot::RwLock;
+#![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+// The test harness always needs `std`
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+#[cfg(feature = "std")]
 use nanoserde::{DeJson, SerJson};
-use std::collections::HashMap;
 
+use crate::compat::{prelude::*, Lazy, Map, RwLock};
+
+pub mod ab;
+mod animate;
 mod api;
+pub mod args;
 mod boolean;
+mod compat;
+mod conflict;
+mod constraint;
+mod derived;
+#[cfg(feature = "std")]
+mod env;
+mod expr;
 mod float;
 mod int;
-
-pub use tuna_macros::tuna;
-
-pub type TunaState = HashMap<String, HashMap<String, Tuneable>>;
-
-lazy_static::lazy_static! {
-    #[doc(hidden)]
-    pub static ref TUNA_STATE: RwLock<TunaState> = RwLock::new(Default::default());
-}
-
-#[derive(Debug, SerJson, DeJson, Clone)]
+mod introspect;
+mod listener;
+pub mod optimise;
+#[cfg(feature = "std")]
+mod overrides;
+mod range;
+mod registry;
+#[cfg(feature = "std")]
+pub mod replay;
+mod rng;
+pub mod schema;
+mod search;
+mod snapshot;
+mod source;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "std")]
+pub mod sweep;
+mod transaction;
+mod validate;
+
+pub use tuna_macros::{tuna, Tuneable};
+
+pub type TunaState = Map<String, Map<String, Tuneable>>;
+
+#[doc(hidden)]
+pub static TUNA_STATE: Lazy<RwLock<TunaState>> = Lazy::new(|| RwLock::new(Default::default()));
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Tuneable {
     Float32(Float32Variable),
     Float64(Float64Variable),
@@ -27,18 +64,63 @@ pub enum Tuneable {
     Uint(u32),
     Size(usize),
     Boolean(BooleanVariable),
+    Derived(DerivedVariable),
 }
 
 impl Tuneable {
-    pub fn apply_to(&self, category: &str, name: &str) {
+    pub fn apply_to(&self, category: &str, name: &str) -> Result<(), SetError> {
+        self.apply_from(category, name, ChangeSource::Code)
+    }
+
+    /// Like [`Tuneable::apply_to`], attributing the write to `source`
+    pub fn apply_from(
+        &self,
+        category: &str,
+        name: &str,
+        source: ChangeSource,
+    ) -> Result<(), SetError> {
         match self {
-            Self::Float32(v) => api::set::<Float32>(category, name, v.current),
-            Self::Float64(v) => api::set::<Float64>(category, name, v.current),
-            Self::Boolean(v) => api::set::<Boolean>(category, name, v.current),
-            Self::Int32(v) => api::set::<Int32>(category, name, v.current),
-            Self::Int64(v) => api::set::<Int64>(category, name, v.current),
+            Self::Float32(v) => {
+                transaction::stage_from::<Float32>(category, name, v.current, source)
+            }
+            Self::Float64(v) => {
+                transaction::stage_from::<Float64>(category, name, v.current, source)
+            }
+            Self::Boolean(v) => {
+                transaction::stage_from::<Boolean>(category, name, v.current, source)
+            }
+            Self::Int32(v) => transaction::stage_from::<Int32>(category, name, v.current, source),
+            Self::Int64(v) => transaction::stage_from::<Int64>(category, name, v.current, source),
+            Self::Derived(_) => Err(SetError::ReadOnly),
             _ => unreachable!(),
-        };
+        }
+    }
+
+    /// The type of the variable
+    pub fn kind(&self) -> Kind {
+        match self {
+            Self::Float32(_) => Kind::Float32,
+            Self::Float64(_) => Kind::Float64,
+            Self::Int32(_) => Kind::Int32,
+            Self::Int64(_) => Kind::Int64,
+            Self::Uint(_) => Kind::Uint,
+            Self::Size(_) => Kind::Size,
+            Self::Boolean(_) => Kind::Boolean,
+            Self::Derived(_) => Kind::Derived,
+        }
+    }
+
+    /// The current value as a number, if it has one. Booleans are `1.0` or `0.0`.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float32(v) => Some(v.current as f64),
+            Self::Float64(v) => Some(v.current),
+            Self::Int32(v) => Some(v.current as f64),
+            Self::Int64(v) => Some(v.current as f64),
+            Self::Boolean(v) => Some(if v.current { 1.0 } else { 0.0 }),
+            Self::Derived(v) => Some(v.current),
+            _ => None,
+        }
     }
 }
 
@@ -104,24 +186,75 @@ macro_rules! impl_tuneable {
                     default,
                     min,
                     max,
+                    soft_min: None,
+                    soft_max: None,
+                    mode: $crate::RangeMode::Clamp,
+                    description: None,
+                }
+            }
+
+            /// Suggest a range to tools, e.g. for the extents of a slider,
+            /// without limiting writes like `min` and `max` do
+            pub const fn with_soft_range(self, min: Option<$res>, max: Option<$res>) -> Self {
+                Self {
+                    soft_min: min,
+                    soft_max: max,
+                    ..self
+                }
+            }
+
+            /// Wrap values around the range instead of clamping them, e.g. for
+            /// angles. Requires both `min` and `max`.
+            pub const fn wrapping(self) -> Self {
+                Self {
+                    mode: $crate::RangeMode::Wrap,
+                    ..self
+                }
+            }
+
+            /// Whether values outside the range are clamped or wrapped
+            pub const fn mode(&self) -> $crate::RangeMode {
+                self.mode
+            }
+
+            /// Attach a description, shown by tools and exported in the schema
+            pub const fn with_description(self, description: &'static str) -> Self {
+                Self {
+                    description: Some(description),
+                    ..self
                 }
             }
 
             /// Explicitly register the float with tuna. This is not required, but
             /// it'll reduce risk of stuttering when variables get registered.
             pub fn register(&self) {
-                crate::register(self.category, self.name, self)
+                crate::register(self.category, self.name, self);
+
+                if let Some(description) = self.description {
+                    crate::set_description(self.category, self.name, description);
+                }
             }
 
             /// Read the variable from tuna. This will automatically call register on a
-            /// lookup miss, and return the default value.
+            /// lookup miss, and return the default value. If the name is registered
+            /// with another type, the default is returned without registering.
             pub fn read(&self) -> $res {
                 crate::get::<$typ>(self.category, self.name).unwrap_or_else(|| {
-                    self.register();
+                    if !crate::is_registered(self.category, self.name) {
+                        self.register();
+                    }
                     self.default
                 })
             }
 
+            /// Read the variable from a snapshot without locking. Returns the
+            /// default value if it wasn't registered when the snapshot was taken.
+            pub fn read_in(&self, snapshot: &crate::Snapshot) -> $res {
+                snapshot
+                    .get::<$typ>(self.category, self.name)
+                    .unwrap_or(self.default)
+            }
+
             /// Update the stored value. Will do nothing if not registered.
             pub fn write(&self, value: $res) {
                 #[cfg(debug_assertions)]
@@ -137,6 +270,61 @@ macro_rules! impl_tuneable {
             pub fn reset(&self) {
                 crate::reset::<$typ>(self.category, self.name);
             }
+
+            /// Override the value for the current thread until the guard is
+            /// dropped, e.g. to force a value in a test. The value is clamped
+            /// like a write.
+            #[cfg(feature = "std")]
+            pub fn override_scoped(&self, value: $res) -> crate::OverrideGuard {
+                crate::overrides::push(self.category, self.name, self, value)
+            }
+
+            /// Attach a validator that runs on every write to this variable,
+            /// before clamping. It can adjust the value or reject it with a
+            /// message.
+            pub fn add_validator(
+                &self,
+                validator: impl Fn($res) -> Result<$res, String> + Send + Sync + 'static,
+            ) {
+                crate::add_validator::<$typ, _>(self.category, self.name, validator);
+            }
+        }
+
+        impl $var {
+            /// The current value
+            pub fn current(&self) -> $res {
+                self.current
+            }
+
+            /// The value the variable was registered with
+            pub fn default(&self) -> $res {
+                self.default
+            }
+
+            /// The lower bound, if any
+            pub fn min(&self) -> Option<$res> {
+                self.min
+            }
+
+            /// The upper bound, if any
+            pub fn max(&self) -> Option<$res> {
+                self.max
+            }
+
+            /// The lower end of the suggested range, if any
+            pub fn soft_min(&self) -> Option<$res> {
+                self.soft_min
+            }
+
+            /// The upper end of the suggested range, if any
+            pub fn soft_max(&self) -> Option<$res> {
+                self.soft_max
+            }
+
+            /// Whether values outside the range are clamped or wrapped
+            pub fn mode(&self) -> $crate::RangeMode {
+                self.mode
+            }
         }
 
         impl $crate::api::AsTuneable for $typ {
@@ -144,7 +332,13 @@ macro_rules! impl_tuneable {
 
             fn make_tuneable(&self) -> Tuneable {
                 let $typ {
-                    default, min, max, ..
+                    default,
+                    min,
+                    max,
+                    soft_min,
+                    soft_max,
+                    mode,
+                    ..
                 } = self;
 
                 let var = $var {
@@ -153,6 +347,9 @@ macro_rules! impl_tuneable {
                     default: *default,
                     min: *min,
                     max: *max,
+                    soft_min: *soft_min,
+                    soft_max: *soft_max,
+                    mode: *mode,
                     current: *default,
                 };
 
@@ -162,6 +359,13 @@ macro_rules! impl_tuneable {
             fn update(tuneable: &mut Tuneable, var: $res) -> bool {
                 match tuneable {
                     Tuneable::$typ(self_) => {
+                        let var = match (self_.mode, self_.min, self_.max) {
+                            ($crate::RangeMode::Wrap, Some(min), Some(max)) => {
+                                $crate::range::Wrap::wrap(var, min, max)
+                            }
+                            _ => var,
+                        };
+
                         let var = if let Some(min) = self_.min {
                             var.max(min)
                         } else {
@@ -197,6 +401,8 @@ macro_rules! impl_tuneable {
     };
 }
 
+pub use crate::range::RangeMode;
+
 pub use crate::float::{Float32, Float64};
 use float::{Float32Variable, Float64Variable};
 impl_tuneable!(Float32, Float32Variable, f32);
@@ -211,4 +417,54 @@ pub use crate::boolean::Boolean;
 use boolean::BooleanVariable;
 impl_tuneable_simple!(Boolean, BooleanVariable, bool);
 
+pub use crate::animate::{animate, animations, is_animating, stop_animation, tick, Animator};
 pub use crate::api::*;
+
+pub use crate::conflict::{
+    clear_conflicts, conflict_policy, conflicts, set_conflict_policy, Conflict, ConflictPolicy,
+};
+
+pub use crate::derived::{register_derived, Derived};
+use derived::DerivedVariable;
+
+#[cfg(feature = "std")]
+pub use crate::env::{apply_env, EnvReport};
+
+pub use crate::introspect::{
+    all, categories, describe, set_description, variables, Descriptor, Kind,
+};
+
+pub use crate::search::{
+    matching, reset_matching, reset_matching_from, search, set_matching, MatchValue,
+};
+
+#[cfg(feature = "std")]
+pub use crate::overrides::{with_overrides, OverrideGuard};
+
+pub use crate::snapshot::{frame, SharedState, Snapshot};
+
+pub use crate::source::{last_source, ChangeSource};
+
+#[cfg(feature = "auto-register")]
+pub use crate::registry::register_all;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::api::get_locked;
+    #[cfg(feature = "auto-register")]
+    pub use crate::registry::REGISTRATIONS;
+    #[cfg(feature = "auto-register")]
+    pub use linkme;
+}
+
+#[cfg(feature = "stats")]
+pub use crate::stats::{clear_stats, never_read, stats, Stats};
+
+pub use crate::transaction::{
+    commit, is_staging, set_staging, stage, stage_from, transaction, Transaction,
+};
+
+pub use crate::listener::{add_failure_listener, add_listener, remove_listener, ListenerId};
+
+pub use crate::constraint::{add_constraint, clear_constraints, Constraint};
+pub use crate::validate::{add_validator, clear_validators};
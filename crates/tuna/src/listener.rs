@@ -0,0 +1,124 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Callbacks invoked after a variable has changed, or after a staged write failed
+to commit.
+*/
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{
+    compat::{prelude::*, Lazy, RwLock},
+    ChangeSource, SetError, Tuneable,
+};
+
+type Listener = Arc<dyn Fn(&str, &str, &Tuneable, &ChangeSource) + Send + Sync>;
+type FailureListener = Arc<dyn Fn(&str, &str, &SetError, &ChangeSource) + Send + Sync>;
+
+/// Handle to a registered listener, used to remove it again
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ListenerId(usize);
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+static LISTENERS: Lazy<RwLock<Vec<(ListenerId, Listener)>>> =
+    Lazy::new(|| RwLock::new(Default::default()));
+static FAILURE_LISTENERS: Lazy<RwLock<Vec<(ListenerId, FailureListener)>>> =
+    Lazy::new(|| RwLock::new(Default::default()));
+
+/// Add a listener that is called with the category, name, new state and the
+/// source of the write every time a variable is written or reset. Listeners run
+/// on the writing thread, after the write is visible, and may themselves write
+/// variables or add and remove listeners.
+pub fn add_listener(
+    listener: impl Fn(&str, &str, &Tuneable, &ChangeSource) + Send + Sync + 'static,
+) -> ListenerId {
+    let id = ListenerId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    LISTENERS.write().push((id, Arc::new(listener)));
+    id
+}
+
+/// Add a listener that is called with the category, name, reason and source
+/// of every staged write that fails when it's committed, e.g. because another
+/// write made it violate a constraint. Writes that fail while being staged
+/// are reported to the caller of `stage` instead.
+pub fn add_failure_listener(
+    listener: impl Fn(&str, &str, &SetError, &ChangeSource) + Send + Sync + 'static,
+) -> ListenerId {
+    let id = ListenerId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    FAILURE_LISTENERS.write().push((id, Arc::new(listener)));
+    id
+}
+
+/// Remove a listener, added with either `add_listener` or
+/// `add_failure_listener`
+pub fn remove_listener(id: ListenerId) {
+    LISTENERS.write().retain(|(other, _)| *other != id);
+    FAILURE_LISTENERS.write().retain(|(other, _)| *other != id);
+}
+
+pub(crate) fn has_listeners() -> bool {
+    !LISTENERS.read().is_empty()
+}
+
+/// Call every listener with the changes. The listeners are cloned out first,
+/// so they run without the lock held.
+pub(crate) fn notify(changes: &[(String, String, Tuneable, ChangeSource)]) {
+    let listeners = LISTENERS
+        .read()
+        .iter()
+        .map(|(_, listener)| listener.clone())
+        .collect::<Vec<_>>();
+
+    for (category, name, tuneable, source) in changes {
+        for listener in &listeners {
+            listener(category, name, tuneable, source);
+        }
+    }
+}
+
+pub(crate) fn notify_failures(failures: &[(String, String, SetError, ChangeSource)]) {
+    let listeners = FAILURE_LISTENERS
+        .read()
+        .iter()
+        .map(|(_, listener)| listener.clone())
+        .collect::<Vec<_>>();
+
+    for (category, name, error, source) in failures {
+        for listener in &listeners {
+            listener(category, name, error, source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_listener, remove_listener};
+    use crate::Float32;
+    use serial_test::serial;
+
+    const TEST_LEADER: Float32 = Float32::new("listener", "leader", 1.0, None, None);
+    const TEST_FOLLOWER: Float32 = Float32::new("listener", "follower", 1.0, None, None);
+
+    #[test]
+    #[serial]
+    fn reentrant() {
+        TEST_LEADER.register();
+        TEST_FOLLOWER.register();
+
+        let listener = add_listener(|category, name, _, _| {
+            if (category, name) == ("listener", "leader") {
+                TEST_FOLLOWER.write(TEST_LEADER.read());
+                remove_listener(add_listener(|_, _, _, _| {}));
+            }
+        });
+
+        TEST_LEADER.write(2.0);
+        remove_listener(listener);
+
+        assert_eq!(TEST_FOLLOWER.read(), 2.0);
+    }
+}
@@ -0,0 +1,454 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Black-box optimisation of numeric variables with the Nelder–Mead simplex
+method.
+
+The search starts from the variables' current values and stays within their
+`min` and `max`, wrapping around for variables that wrap. Every candidate is
+applied with [`crate::set`] semantics before the objective is evaluated, so
+the objective can simply run the simulation and measure the result. When done, the best candidate is applied
+and returned together with a [`Snapshot`] of it.
+
+```no_run
+use tuna::{optimise::Optimiser, Float32};
+
+const GAIN: Float32 = Float32::new("controller", "gain", 1.0, Some(0.0), Some(10.0));
+const DAMPING: Float32 = Float32::new("controller", "damping", 0.1, Some(0.0), Some(1.0));
+
+GAIN.register();
+DAMPING.register();
+
+let best = Optimiser::new()
+    .variable(GAIN)
+    .variable(DAMPING)
+    .max_evaluations(200)
+    .minimise(|_| {
+        // run the simulation and measure the error
+        0.0
+    })
+    .unwrap();
+
+println!("best error {} at {:?}", best.score, best.values);
+```
+*/
+
+use core::cell::Cell;
+
+use crate::{
+    api::try_set_f64,
+    compat::{math, prelude::*},
+    range::Wrap,
+    ChangeSource, Float32, Float64, Int32, Int64, RangeMode, SetError, Snapshot, TUNA_STATE,
+};
+
+/// A variable the optimiser is allowed to change
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dimension {
+    pub category: String,
+    pub name: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Integer variables are only evaluated at whole numbers
+    pub integer: bool,
+    /// Whether values outside of the range are clamped or wrapped
+    pub mode: RangeMode,
+}
+
+impl Dimension {
+    fn wraps(&self) -> bool {
+        self.mode == RangeMode::Wrap && self.min.is_some() && self.max.is_some()
+    }
+
+    /// Round and clamp a point of the simplex. Wrapping variables are left
+    /// unwrapped, so the simplex can move across the ends of their range.
+    fn bound(&self, value: f64) -> f64 {
+        let value = if self.integer {
+            math::round(value)
+        } else {
+            value
+        };
+
+        if self.wraps() {
+            return value;
+        }
+
+        let value = self.min.map_or(value, |min| value.max(min));
+        self.max.map_or(value, |max| value.min(max))
+    }
+
+    /// The value a point of the simplex is stored as
+    fn limit(&self, value: f64) -> f64 {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if self.mode == RangeMode::Wrap => value.wrap(min, max),
+            _ => value,
+        }
+    }
+
+    /// The size of the first step away from `value`
+    fn step(&self, value: f64) -> f64 {
+        let step = match (self.min, self.max) {
+            (Some(min), Some(max)) => (max - min) * 0.1,
+            _ if value == 0.0 => 1.0,
+            _ => value * 0.1,
+        };
+
+        let step = if self.integer && step.max(-step) < 1.0 {
+            1.0
+        } else {
+            step
+        };
+
+        // Step inwards when already at the upper bound, unless it wraps
+        match self.max {
+            Some(max) if self.mode == RangeMode::Clamp && value + step > max => -step,
+            _ => step,
+        }
+    }
+}
+
+macro_rules! impl_dimension {
+    ($typ:ty, $integer:expr) => {
+        impl From<$typ> for Dimension {
+            fn from(definition: $typ) -> Self {
+                Self {
+                    category: definition.category.to_owned(),
+                    name: definition.name.to_owned(),
+                    min: definition.min.map(|v| v as f64),
+                    max: definition.max.map(|v| v as f64),
+                    integer: $integer,
+                    mode: definition.mode,
+                }
+            }
+        }
+    };
+}
+
+impl_dimension!(Float32, false);
+impl_dimension!(Float64, false);
+impl_dimension!(Int32, true);
+impl_dimension!(Int64, true);
+
+/// The best candidate found
+#[derive(Clone, Debug)]
+pub struct Optimum {
+    /// Values in the order the variables were added
+    pub values: Vec<f64>,
+    pub score: f64,
+    /// The number of times the objective was called
+    pub evaluations: usize,
+    /// All variables, with the best candidate applied
+    pub snapshot: Snapshot,
+}
+
+/// A Nelder–Mead optimiser over a set of variables
+#[derive(Clone, Debug)]
+pub struct Optimiser {
+    dimensions: Vec<Dimension>,
+    max_evaluations: usize,
+    tolerance: f64,
+}
+
+impl Default for Optimiser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Optimiser {
+    /// Create an optimiser without variables, add them with
+    /// [`Optimiser::variable`] or [`Optimiser::dimension`]
+    pub fn new() -> Self {
+        Self {
+            dimensions: vec![],
+            max_evaluations: 500,
+            tolerance: 1e-6,
+        }
+    }
+
+    /// Add a variable to optimise, which must be registered by the time
+    /// [`Optimiser::minimise`] is called
+    pub fn variable(mut self, definition: impl Into<Dimension>) -> Self {
+        self.dimensions.push(definition.into());
+        self
+    }
+
+    /// Add a variable by name, e.g. one registered by a tool
+    pub fn dimension(mut self, dimension: Dimension) -> Self {
+        self.dimensions.push(dimension);
+        self
+    }
+
+    /// Stop after calling the objective this many times, including the calls
+    /// for the initial simplex. Defaults to 500.
+    pub fn max_evaluations(self, max_evaluations: usize) -> Self {
+        Self {
+            max_evaluations,
+            ..self
+        }
+    }
+
+    /// Stop when the scores across the simplex differ by less than this.
+    /// Defaults to `1e-6`.
+    pub fn tolerance(self, tolerance: f64) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    /// Search for the values with the lowest score. To maximise, negate the
+    /// score.
+    pub fn minimise(&self, mut objective: impl FnMut(&[f64]) -> f64) -> Result<Optimum, SetError> {
+        let start = {
+            let tuna = TUNA_STATE.read();
+            self.dimensions
+                .iter()
+                .map(|d| {
+                    tuna.get(&d.category)
+                        .and_then(|group| group.get(&d.name))
+                        .and_then(|v| v.as_f64())
+                        .map(|v| d.bound(v))
+                        .ok_or(SetError::NotFound)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let evaluations = Cell::new(0);
+        let mut evaluate = |point: Vec<f64>| -> Result<(Vec<f64>, f64), SetError> {
+            let point = point
+                .iter()
+                .zip(&self.dimensions)
+                .map(|(v, d)| d.bound(*v))
+                .collect::<Vec<_>>();
+
+            // Candidates beyond the budget aren't evaluated, and lose against
+            // every one that was
+            if evaluations.get() >= self.max_evaluations {
+                return Ok((point, f64::INFINITY));
+            }
+
+            let values = self.limit(&point);
+            self.apply(&values)?;
+            evaluations.set(evaluations.get() + 1);
+            let score = objective(&values);
+            // Treat failed evaluations as infinitely bad
+            Ok((point, if score.is_nan() { f64::INFINITY } else { score }))
+        };
+
+        let mut simplex = vec![evaluate(start.clone())?];
+        for (i, dimension) in self.dimensions.iter().enumerate() {
+            let mut vertex = start.clone();
+            vertex[i] += dimension.step(start[i]);
+            simplex.push(evaluate(vertex)?);
+        }
+
+        let n = self.dimensions.len();
+        while evaluations.get() < self.max_evaluations && n > 0 {
+            simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+            if simplex[n].1 - simplex[0].1 <= self.tolerance {
+                break;
+            }
+
+            let centroid = (0..n)
+                .map(|i| simplex[..n].iter().map(|v| v.0[i]).sum::<f64>() / n as f64)
+                .collect::<Vec<_>>();
+            let towards = |t: f64| {
+                centroid
+                    .iter()
+                    .zip(&simplex[n].0)
+                    .map(|(c, w)| c + t * (w - c))
+                    .collect::<Vec<_>>()
+            };
+
+            let reflected = evaluate(towards(-1.0))?;
+            if reflected.1 < simplex[0].1 {
+                let expanded = evaluate(towards(-2.0))?;
+                simplex[n] = if expanded.1 < reflected.1 {
+                    expanded
+                } else {
+                    reflected
+                };
+            } else if reflected.1 < simplex[n - 1].1 {
+                simplex[n] = reflected;
+            } else {
+                let contracted = if reflected.1 < simplex[n].1 {
+                    evaluate(towards(-0.5))?
+                } else {
+                    evaluate(towards(0.5))?
+                };
+
+                if contracted.1 < simplex[n].1.min(reflected.1) {
+                    simplex[n] = contracted;
+                } else {
+                    // Shrink everything towards the best vertex
+                    let best = simplex[0].0.clone();
+                    for vertex in simplex.iter_mut().skip(1) {
+                        let point = best
+                            .iter()
+                            .zip(&vertex.0)
+                            .map(|(b, v)| b + 0.5 * (v - b))
+                            .collect();
+                        *vertex = evaluate(point)?;
+                    }
+                }
+            }
+        }
+
+        let (point, score) = simplex
+            .into_iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or_default();
+        let values = self.limit(&point);
+        self.apply(&values)?;
+
+        Ok(Optimum {
+            values,
+            score,
+            evaluations: evaluations.get(),
+            snapshot: crate::frame(),
+        })
+    }
+
+    fn limit(&self, point: &[f64]) -> Vec<f64> {
+        point
+            .iter()
+            .zip(&self.dimensions)
+            .map(|(v, d)| d.limit(*v))
+            .collect()
+    }
+
+    fn apply(&self, values: &[f64]) -> Result<(), SetError> {
+        for (dimension, value) in self.dimensions.iter().zip(values) {
+            try_set_f64(
+                &dimension.category,
+                &dimension.name,
+                *value,
+                ChangeSource::Code,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dimension, Optimiser};
+    use crate::compat::prelude::*;
+    use crate::{Float32, Float64, Int32, RangeMode, SetError};
+    use serial_test::serial;
+
+    const TEST_X: Float64 = Float64::new("optimise", "x", 0.0, Some(-5.0), Some(5.0));
+    const TEST_Y: Float32 = Float32::new("optimise", "y", 0.0, Some(-5.0), Some(5.0));
+    const TEST_STEPS: Int32 = Int32::new("optimise", "steps", 1, Some(1), Some(20));
+
+    #[test]
+    #[serial]
+    fn quadratic() {
+        TEST_X.register();
+        TEST_Y.register();
+        TEST_X.reset();
+        TEST_Y.reset();
+
+        let best = Optimiser::new()
+            .variable(TEST_X)
+            .variable(TEST_Y)
+            .max_evaluations(400)
+            .tolerance(1e-10)
+            .minimise(|_| {
+                let x = TEST_X.read();
+                let y = TEST_Y.read() as f64;
+                (x - 1.5) * (x - 1.5) + (y + 2.0) * (y + 2.0)
+            })
+            .unwrap();
+
+        assert!((best.values[0] - 1.5).abs() < 1e-2, "{:?}", best);
+        assert!((best.values[1] + 2.0).abs() < 1e-2, "{:?}", best);
+        assert!(best.evaluations <= 400);
+
+        // The best candidate is left applied
+        assert!((TEST_X.read() - 1.5).abs() < 1e-2);
+        assert!((TEST_X.read_in(&best.snapshot) - 1.5).abs() < 1e-2);
+    }
+
+    #[test]
+    #[serial]
+    fn respects_bounds() {
+        TEST_STEPS.register();
+        TEST_STEPS.reset();
+
+        // The unconstrained optimum is far above the maximum
+        let best = Optimiser::new()
+            .variable(TEST_STEPS)
+            .minimise(|values| {
+                assert_eq!(values[0], values[0].round());
+                assert!((1.0..=20.0).contains(&values[0]));
+                -(TEST_STEPS.read() as f64)
+            })
+            .unwrap();
+
+        assert_eq!(best.values, vec![20.0]);
+        assert_eq!(TEST_STEPS.read(), 20);
+    }
+
+    const TEST_HEADING: Float64 =
+        Float64::new("optimise", "heading", 340.0, Some(0.0), Some(360.0)).wrapping();
+
+    #[test]
+    #[serial]
+    fn wrapping() {
+        TEST_HEADING.register();
+        TEST_HEADING.reset();
+
+        // The target is closest going past 360, which clamping would stop at
+        let best = Optimiser::new()
+            .variable(TEST_HEADING)
+            .tolerance(1e-10)
+            .minimise(|values| {
+                assert!((0.0..360.0).contains(&values[0]));
+                let distance = (values[0] - 10.0).rem_euclid(360.0);
+                distance.min(360.0 - distance)
+            })
+            .unwrap();
+
+        assert!(best.score < 1e-2, "{:?}", best);
+    }
+
+    #[test]
+    #[serial]
+    fn budget() {
+        TEST_X.register();
+        TEST_Y.register();
+
+        let mut calls = 0;
+        let best = Optimiser::new()
+            .variable(TEST_X)
+            .variable(TEST_Y)
+            .max_evaluations(2)
+            .minimise(|_| {
+                calls += 1;
+                0.0
+            })
+            .unwrap();
+
+        // The initial simplex alone would take three
+        assert_eq!(calls, 2);
+        assert_eq!(best.evaluations, 2);
+    }
+
+    #[test]
+    #[serial]
+    fn unknown_variable() {
+        let result = Optimiser::new()
+            .dimension(Dimension {
+                category: "optimise".to_owned(),
+                name: "missing".to_owned(),
+                min: None,
+                max: None,
+                integer: false,
+                mode: RangeMode::Clamp,
+            })
+            .minimise(|_| 0.0);
+
+        assert_eq!(result.err(), Some(SetError::NotFound));
+    }
+}
@@ -0,0 +1,174 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Temporary per-thread overrides, mainly for tests.
+
+An override takes precedence over the global value when read from the thread
+that created it, through `read`, `read_in` and [`crate::get`], and is removed
+when its [`OverrideGuard`] is dropped. Other threads keep seeing the global
+value, so tests that override the same variable can run in parallel without
+`serial_test`.
+
+Writes still go to the global value, and are hidden on this thread while the
+override is active.
+*/
+
+use core::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{AsTuneable, Tuneable};
+
+struct Layer {
+    id: u64,
+    category: &'static str,
+    name: &'static str,
+    value: Tuneable,
+}
+
+thread_local! {
+    static LAYERS: RefCell<Vec<Layer>> = const { RefCell::new(Vec::new()) };
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Removes an override when dropped, restoring the value the thread saw
+/// before. Guards can be dropped in any order, but not sent to other threads.
+#[must_use = "the override is removed when the guard is dropped"]
+#[derive(Debug)]
+pub struct OverrideGuard {
+    id: u64,
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        // The thread local may already be gone if the guard lives in another
+        // thread local
+        let _ = LAYERS.try_with(|layers| layers.borrow_mut().retain(|layer| layer.id != self.id));
+    }
+}
+
+/// Override a variable for the current thread, starting from its definition
+/// so the value is clamped or wrapped like a write would be.
+pub(crate) fn push<T: AsTuneable>(
+    category: &'static str,
+    name: &'static str,
+    definition: &T,
+    value: T::Result,
+) -> OverrideGuard {
+    let mut tuneable = definition.make_tuneable();
+    T::update(&mut tuneable, value);
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    LAYERS.with(|layers| {
+        layers.borrow_mut().push(Layer {
+            id,
+            category,
+            name,
+            value: tuneable,
+        })
+    });
+
+    OverrideGuard {
+        id,
+        _thread: PhantomData,
+    }
+}
+
+/// The innermost override of a variable on the current thread, if any
+pub(crate) fn get<T: AsTuneable>(category: &str, name: &str) -> Option<T::Result> {
+    LAYERS.with(|layers| {
+        layers
+            .borrow()
+            .iter()
+            .rev()
+            .find(|layer| layer.category == category && layer.name == name)
+            .and_then(|layer| T::from_tuneable(&layer.value))
+    })
+}
+
+/// Run `f` with a set of overrides, removing them afterwards even if `f`
+/// panics.
+///
+/// ```
+/// const VSYNC: tuna::Boolean = tuna::Boolean::new("render", "vsync", true);
+/// const SEED: tuna::Int64 = tuna::Int64::new("world", "seed", 0, None, None);
+///
+/// let vsync = tuna::with_overrides([VSYNC.override_scoped(false), SEED.override_scoped(42)], || {
+///     assert_eq!(SEED.read(), 42);
+///     VSYNC.read()
+/// });
+///
+/// assert!(!vsync);
+/// assert!(VSYNC.read());
+/// ```
+pub fn with_overrides<R>(
+    overrides: impl IntoIterator<Item = OverrideGuard>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _guards = overrides.into_iter().collect::<Vec<_>>();
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_overrides;
+    use crate::{Boolean, Float32, Int32};
+
+    const TEST_VSYNC: Boolean = Boolean::new("overrides", "vsync", true);
+    const TEST_SPEED: Float32 = Float32::new("overrides", "speed", 1.0, Some(0.0), Some(10.0));
+    const TEST_SEED: Int32 = Int32::new("overrides", "seed", 0, None, None);
+
+    #[test]
+    fn scoped() {
+        TEST_SPEED.register();
+
+        {
+            let _outer = TEST_SPEED.override_scoped(2.0);
+            assert_eq!(TEST_SPEED.read(), 2.0);
+            assert_eq!(TEST_SPEED.read_in(&crate::frame()), 2.0);
+
+            {
+                let _inner = TEST_SPEED.override_scoped(20.0);
+                assert_eq!(TEST_SPEED.read(), 10.0);
+            }
+
+            assert_eq!(TEST_SPEED.read(), 2.0);
+
+            // Other threads see the global value
+            let global = std::thread::spawn(|| TEST_SPEED.read()).join().unwrap();
+            assert_eq!(global, 1.0);
+        }
+
+        assert_eq!(TEST_SPEED.read(), 1.0);
+    }
+
+    #[test]
+    fn out_of_order() {
+        let first = TEST_SEED.override_scoped(1);
+        let second = TEST_SEED.override_scoped(2);
+
+        drop(first);
+        assert_eq!(TEST_SEED.read(), 2);
+        drop(second);
+        assert_eq!(TEST_SEED.read(), 0);
+    }
+
+    #[test]
+    fn helper() {
+        let result = std::panic::catch_unwind(|| {
+            with_overrides([TEST_VSYNC.override_scoped(false)], || {
+                assert!(!TEST_VSYNC.read());
+                panic!("test failure");
+            })
+        });
+
+        assert!(result.is_err());
+        assert!(TEST_VSYNC.read());
+    }
+}
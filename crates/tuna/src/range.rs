@@ -0,0 +1,118 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+How numeric variables treat values outside of their range.
+*/
+
+use crate::compat::math;
+#[cfg(feature = "std")]
+use nanoserde::{DeJson, SerJson};
+
+/// What happens to writes outside of `min` and `max`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(SerJson, DeJson))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RangeMode {
+    /// Values are clamped to the nearest bound
+    #[default]
+    Clamp,
+    /// Values wrap around, with `max` being the same point as `min`, e.g.
+    /// `370` becomes `10` in `0..360`. Falls back to clamping unless both
+    /// bounds are set.
+    Wrap,
+}
+
+/// Numeric types that can be wrapped into a range
+pub(crate) trait Wrap: Sized {
+    /// Wrap into `[min, max)`
+    fn wrap(self, min: Self, max: Self) -> Self;
+}
+
+macro_rules! impl_wrap_float {
+    ($ty:ident) => {
+        impl Wrap for $ty {
+            fn wrap(self, min: Self, max: Self) -> Self {
+                let period = max as f64 - min as f64;
+                if period <= 0.0 || !self.is_finite() {
+                    return self.max(min).min(max);
+                }
+
+                let offset = self as f64 - min as f64;
+                let wrapped = (min as f64 + offset - period * math::floor(offset / period)) as $ty;
+
+                // Rounding can land exactly on `max`, which is the same point
+                if wrapped >= max {
+                    min
+                } else {
+                    wrapped
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_wrap_int {
+    ($ty:ident) => {
+        impl Wrap for $ty {
+            fn wrap(self, min: Self, max: Self) -> Self {
+                let period = max as i128 - min as i128;
+                if period <= 0 {
+                    return self.max(min).min(max);
+                }
+
+                (min as i128 + (self as i128 - min as i128).rem_euclid(period)) as $ty
+            }
+        }
+    };
+}
+
+impl_wrap_float!(f32);
+impl_wrap_float!(f64);
+impl_wrap_int!(i32);
+impl_wrap_int!(i64);
+
+#[cfg(test)]
+mod tests {
+    use super::{RangeMode, Wrap};
+    use crate::{Float32, Int32};
+    use serial_test::serial;
+
+    const TEST_YAW: Float32 = Float32::new("range", "yaw", 0.0, Some(0.0), Some(360.0)).wrapping();
+    const TEST_HUE: Int32 = Int32::new("range", "hue", 0, Some(0), Some(360)).wrapping();
+    const TEST_PITCH: Float32 = Float32::new("range", "pitch", 0.0, Some(-90.0), Some(90.0));
+
+    #[test]
+    fn wrap() {
+        assert_eq!(370.0f32.wrap(0.0, 360.0), 10.0);
+        assert_eq!((-10.0f64).wrap(0.0, 360.0), 350.0);
+        assert_eq!(360.0f64.wrap(0.0, 360.0), 0.0);
+        assert_eq!(190.0f64.wrap(-180.0, 180.0), -170.0);
+        assert_eq!(725i32.wrap(0, 360), 5);
+        assert_eq!((-1i64).wrap(0, 360), 359);
+        assert_eq!(i32::MAX.wrap(i32::MIN, i32::MAX), i32::MIN);
+    }
+
+    #[test]
+    #[serial]
+    fn write() {
+        TEST_YAW.register();
+        TEST_HUE.register();
+        TEST_PITCH.register();
+
+        TEST_YAW.write(370.0);
+        assert_eq!(TEST_YAW.read(), 10.0);
+        TEST_YAW.write(-45.0);
+        assert_eq!(TEST_YAW.read(), 315.0);
+
+        TEST_HUE.write(400);
+        assert_eq!(TEST_HUE.read(), 40);
+
+        TEST_PITCH.write(120.0);
+        assert_eq!(TEST_PITCH.read(), 90.0);
+
+        assert_eq!(TEST_YAW.mode(), RangeMode::Wrap);
+        assert_eq!(TEST_PITCH.mode(), RangeMode::Clamp);
+    }
+}
@@ -0,0 +1,98 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+A link-time registry of every tuneable in the binary, across crates.
+
+`#[tuna::tuna]` modules and `#[derive(Tuneable)]` structs add their `register`
+function to a distributed slice, and [`register_all`] calls all of them. Plain
+constant definitions can be added with [`auto_register!`](crate::auto_register).
+
+The slice is built by the linker, so nothing runs before `main`. This needs the
+opt-in `auto-register` feature, as `linkme` doesn't support every target, such
+as `wasm32`. Without it, [`auto_register!`](crate::auto_register) and the
+macros submit nothing and `register_all` doesn't exist.
+*/
+
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+#[linkme::distributed_slice]
+pub static REGISTRATIONS: [fn()];
+
+/// Register every tuneable submitted to the registry, in any crate linked
+/// into the binary. Returns the number of registration functions called.
+///
+/// ```
+/// const ENABLE_LOGGING: tuna::Boolean = tuna::Boolean::new("logging", "enable", false);
+/// tuna::auto_register!(ENABLE_LOGGING);
+///
+/// tuna::register_all();
+/// assert!(tuna::is_registered("logging", "enable"));
+/// ```
+#[cfg(feature = "auto-register")]
+pub fn register_all() -> usize {
+    for register in REGISTRATIONS {
+        register();
+    }
+
+    REGISTRATIONS.len()
+}
+
+#[doc(hidden)]
+#[cfg(feature = "auto-register")]
+#[macro_export]
+macro_rules! __submit {
+    ($register:expr) => {
+        const _: () = {
+            #[$crate::__private::linkme::distributed_slice($crate::__private::REGISTRATIONS)]
+            #[linkme(crate = $crate::__private::linkme)]
+            static REGISTRATION: fn() = $register;
+        };
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "auto-register"))]
+#[macro_export]
+macro_rules! __submit {
+    ($register:expr) => {};
+}
+
+/// Add constant definitions to the registry, so [`register_all`] registers
+/// them.
+///
+/// ```
+/// const SPEED: tuna::Float32 = tuna::Float32::new("player", "speed", 4.0, None, None);
+/// const JUMP: tuna::Boolean = tuna::Boolean::new("player", "jump", true);
+///
+/// tuna::auto_register!(SPEED, JUMP);
+/// ```
+#[macro_export]
+macro_rules! auto_register {
+    ($($definition:path),+ $(,)?) => {
+        $crate::__submit!(|| {
+            $($definition.register();)+
+        });
+    };
+}
+
+#[cfg(all(test, feature = "auto-register"))]
+mod tests {
+    use super::register_all;
+    use crate::{Float32, Int32};
+    use serial_test::serial;
+
+    const TEST_SPEED: Float32 = Float32::new("registry", "speed", 4.0, None, None);
+    const TEST_LIVES: Int32 = Int32::new("registry", "lives", 3, None, None);
+
+    crate::auto_register!(TEST_SPEED, TEST_LIVES);
+
+    #[test]
+    #[serial]
+    fn registers() {
+        assert!(register_all() >= 1);
+        assert!(crate::is_registered("registry", "speed"));
+        assert!(crate::is_registered("registry", "lives"));
+    }
+}
@@ -0,0 +1,420 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Recording and deterministic replay of variable changes.
+
+The application reports its frame number with [`advance`] once per frame. A
+[`Recorder`] writes every change together with the current frame, one change
+per line:
+
+```text
+<frame>\t<category>\t<name>\t<type>\t<value>
+```
+
+where `<type>` is one of `f32`, `f64`, `i32`, `i64` or `bool`. A recording
+loaded with [`play`] or [`play_file`] is re-applied by [`advance`] when the
+same frame numbers are reached.
+
+Variables rebalanced by a constraint are recorded as changes of their own, so
+replayed values are written back as they were stored, without running
+validators or constraints again.
+*/
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    api::{finish_write, set_locked},
+    compat::{Lazy, Mutex},
+    listener::{add_listener, remove_listener, ListenerId},
+    source, Boolean, ChangeSource, Float32, Float64, Int32, Int64, SetError, TunaState, Tuneable,
+    TUNA_STATE,
+};
+
+static FRAME: AtomicU64 = AtomicU64::new(0);
+
+/// A single recorded change
+#[derive(Clone, Debug, PartialEq)]
+struct Change {
+    frame: u64,
+    category: String,
+    name: String,
+    value: Value,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Float32(f32),
+    Float64(f64),
+    Int32(i32),
+    Int64(i64),
+    Boolean(bool),
+}
+
+impl Value {
+    fn from_tuneable(tuneable: &Tuneable) -> Option<Self> {
+        Some(match tuneable {
+            Tuneable::Float32(v) => Value::Float32(v.current),
+            Tuneable::Float64(v) => Value::Float64(v.current),
+            Tuneable::Int32(v) => Value::Int32(v.current),
+            Tuneable::Int64(v) => Value::Int64(v.current),
+            Tuneable::Boolean(v) => Value::Boolean(v.current),
+            _ => return None,
+        })
+    }
+
+    fn parse(tag: &str, value: &str) -> Option<Self> {
+        Some(match tag {
+            "f32" => Value::Float32(value.parse().ok()?),
+            "f64" => Value::Float64(value.parse().ok()?),
+            "i32" => Value::Int32(value.parse().ok()?),
+            "i64" => Value::Int64(value.parse().ok()?),
+            "bool" => Value::Boolean(value.parse().ok()?),
+            _ => return None,
+        })
+    }
+
+    /// Write the value into the locked state as-is. Returns every variable
+    /// that changed.
+    fn apply(
+        &self,
+        tuna: &mut TunaState,
+        category: &str,
+        name: &str,
+    ) -> Result<Vec<(String, String)>, SetError> {
+        match *self {
+            Value::Float32(v) => set_locked::<Float32>(tuna, category, name, v, false),
+            Value::Float64(v) => set_locked::<Float64>(tuna, category, name, v, false),
+            Value::Int32(v) => set_locked::<Int32>(tuna, category, name, v, false),
+            Value::Int64(v) => set_locked::<Int64>(tuna, category, name, v, false),
+            Value::Boolean(v) => set_locked::<Boolean>(tuna, category, name, v, false),
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t{}\t{}\t", self.frame, self.category, self.name)?;
+        match self.value {
+            Value::Float32(v) => write!(f, "f32\t{}", v),
+            Value::Float64(v) => write!(f, "f64\t{}", v),
+            Value::Int32(v) => write!(f, "i32\t{}", v),
+            Value::Int64(v) => write!(f, "i64\t{}", v),
+            Value::Boolean(v) => write!(f, "bool\t{}", v),
+        }
+    }
+}
+
+impl std::str::FromStr for Change {
+    type Err = io::Error;
+
+    fn from_str(line: &str) -> io::Result<Self> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid recording line: `{}`", line),
+            )
+        };
+
+        let mut parts = line.split('\t');
+        let mut next = || parts.next().ok_or_else(invalid);
+
+        let frame = next()?.parse().map_err(|_| invalid())?;
+        let category = next()?.to_owned();
+        let name = next()?.to_owned();
+        let tag = next()?;
+        let value = Value::parse(tag, next()?).ok_or_else(invalid)?;
+
+        Ok(Change {
+            frame,
+            category,
+            name,
+            value,
+        })
+    }
+}
+
+struct Player {
+    changes: Vec<Change>,
+    position: usize,
+}
+
+static PLAYER: Lazy<Mutex<Option<Player>>> = Lazy::new(|| Mutex::new(None));
+
+/// The frame number last passed to [`advance`]
+pub fn frame() -> u64 {
+    FRAME.load(Ordering::Relaxed)
+}
+
+/// Report the current frame number. Changes recorded from now on are tagged
+/// with this frame, and any loaded recording applies all changes recorded up
+/// to and including it.
+pub fn advance(frame: u64) {
+    FRAME.store(frame, Ordering::Relaxed);
+
+    let due = {
+        let mut player = PLAYER.lock();
+        let player = match player.as_mut() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let start = player.position;
+        while player.position < player.changes.len()
+            && player.changes[player.position].frame <= frame
+        {
+            player.position += 1;
+        }
+
+        player.changes[start..player.position].to_vec()
+    };
+
+    if due.is_empty() {
+        return;
+    }
+
+    // All changes of a frame are written at once, like the writes that
+    // produced them
+    let mut tuna = TUNA_STATE.write();
+    let mut changed = vec![];
+    for change in due {
+        match change
+            .value
+            .apply(&mut tuna, &change.category, &change.name)
+        {
+            Ok(variables) => {
+                for variable in variables {
+                    if !changed.contains(&variable) {
+                        changed.push(variable);
+                    }
+                }
+            }
+            Err(e) => log::warn!(
+                "Failed replaying {}/{} at frame {}: {}",
+                change.category,
+                change.name,
+                change.frame,
+                e
+            ),
+        }
+    }
+
+    source::record(&changed, &ChangeSource::Replay);
+    finish_write(tuna, changed);
+}
+
+/// Load a recording to be replayed by [`advance`], replacing any recording
+/// already loaded.
+pub fn play(reader: impl BufRead) -> io::Result<()> {
+    let mut changes = reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(|line| line?.parse())
+        .collect::<io::Result<Vec<Change>>>()?;
+
+    changes.sort_by_key(|c| c.frame);
+
+    log::debug!("Loaded recording with {} changes", changes.len());
+    *PLAYER.lock() = Some(Player {
+        changes,
+        position: 0,
+    });
+
+    Ok(())
+}
+
+/// Load a recording from a file, see [`play`]
+pub fn play_file(path: impl AsRef<Path>) -> io::Result<()> {
+    play(BufReader::new(File::open(path)?))
+}
+
+/// Unload the current recording
+pub fn stop_playback() {
+    *PLAYER.lock() = None;
+}
+
+/// Check if a loaded recording still has changes left to apply
+pub fn is_playing() -> bool {
+    PLAYER
+        .lock()
+        .as_ref()
+        .is_some_and(|p| p.position < p.changes.len())
+}
+
+/// Records every change to any variable while alive.
+pub struct Recorder<W: Write + Send + 'static> {
+    listener: ListenerId,
+    output: Arc<Mutex<Option<W>>>,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Start recording to a new file
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write + Send + 'static> Recorder<W> {
+    /// Start recording to a writer
+    pub fn new(output: W) -> Self {
+        let output = Arc::new(Mutex::new(Some(output)));
+
+        let sink = output.clone();
+        let listener = add_listener(move |category, name, tuneable, _| {
+            let value = match Value::from_tuneable(tuneable) {
+                Some(v) => v,
+                None => return,
+            };
+
+            let change = Change {
+                frame: frame(),
+                category: category.to_owned(),
+                name: name.to_owned(),
+                value,
+            };
+
+            if let Some(output) = sink.lock().as_mut() {
+                if let Err(e) = writeln!(output, "{}", change) {
+                    log::error!("Failed recording change: {}", e);
+                }
+            }
+        });
+
+        Self { listener, output }
+    }
+
+    /// Stop recording and return the writer, flushed
+    pub fn finish(self) -> io::Result<W> {
+        remove_listener(self.listener);
+
+        let mut output = self
+            .output
+            .lock()
+            .take()
+            .expect("only taken when finishing");
+        output.flush()?;
+        Ok(output)
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for Recorder<W> {
+    fn drop(&mut self) {
+        remove_listener(self.listener);
+
+        if let Some(output) = self.output.lock().as_mut() {
+            if let Err(e) = output.flush() {
+                log::error!("Failed flushing recording: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{advance, is_playing, play, stop_playback, Recorder};
+    use crate::{add_constraint, clear_constraints, Boolean, Constraint, Float32, Int64};
+    use serial_test::serial;
+
+    const TEST_FLOAT: Float32 = Float32::new("replay", "float", 0.5, None, None);
+    const TEST_INT: Int64 = Int64::new("replay", "int", 3, None, None);
+    const TEST_BOOL: Boolean = Boolean::new("replay", "bool", false);
+
+    #[test]
+    #[serial]
+    fn record_and_replay() {
+        TEST_FLOAT.register();
+        TEST_INT.register();
+        TEST_BOOL.register();
+        TEST_FLOAT.reset();
+        TEST_INT.reset();
+        TEST_BOOL.reset();
+
+        let recorder = Recorder::new(vec![]);
+        advance(1);
+        TEST_FLOAT.write(0.25);
+        advance(5);
+        TEST_INT.write(-7);
+        TEST_BOOL.write(true);
+        let recording = String::from_utf8(recorder.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            recording,
+            "1\treplay\tfloat\tf32\t0.25\n5\treplay\tint\ti64\t-7\n5\treplay\tbool\tbool\ttrue\n"
+        );
+
+        TEST_FLOAT.reset();
+        TEST_INT.reset();
+        TEST_BOOL.reset();
+
+        play(recording.as_bytes()).unwrap();
+        advance(0);
+        assert_eq!(TEST_FLOAT.read(), 0.5);
+
+        advance(3);
+        assert_eq!(TEST_FLOAT.read(), 0.25);
+        assert_eq!(TEST_INT.read(), 3);
+
+        advance(5);
+        assert_eq!(TEST_INT.read(), -7);
+        assert!(TEST_BOOL.read());
+        assert!(!is_playing());
+
+        stop_playback();
+    }
+
+    const TEST_WEIGHT_A: Float32 = Float32::new("replay", "weight_a", 0.5, Some(0.0), Some(1.0));
+    const TEST_WEIGHT_B: Float32 = Float32::new("replay", "weight_b", 0.25, Some(0.0), Some(1.0));
+    const TEST_WEIGHT_C: Float32 = Float32::new("replay", "weight_c", 0.25, Some(0.0), Some(1.0));
+
+    #[test]
+    #[serial]
+    fn replay_rebalanced() {
+        let weights = [TEST_WEIGHT_A, TEST_WEIGHT_B, TEST_WEIGHT_C];
+        for weight in &weights {
+            weight.register();
+            weight.reset();
+        }
+        add_constraint(Constraint::sum_to_one(&[
+            ("replay", "weight_a"),
+            ("replay", "weight_b"),
+            ("replay", "weight_c"),
+        ]));
+
+        let recorder = Recorder::new(vec![]);
+        advance(1);
+        TEST_WEIGHT_A.write(0.8);
+        let recording = String::from_utf8(recorder.finish().unwrap()).unwrap();
+        let recorded = weights.iter().map(|w| w.read()).collect::<Vec<_>>();
+
+        for weight in &weights {
+            weight.reset();
+        }
+
+        play(recording.as_bytes()).unwrap();
+        advance(1);
+        assert_eq!(
+            weights.iter().map(|w| w.read()).collect::<Vec<_>>(),
+            recorded
+        );
+
+        clear_constraints();
+        stop_playback();
+    }
+
+    #[test]
+    #[serial]
+    fn invalid_recording() {
+        assert!(play("1\treplay\tfloat\tf32\n".as_bytes()).is_err());
+        assert!(play("x\treplay\tfloat\tf32\t1.0\n".as_bytes()).is_err());
+        assert!(play("1\treplay\tfloat\tu8\t1\n".as_bytes()).is_err());
+    }
+}
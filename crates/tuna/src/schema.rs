@@ -0,0 +1,249 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Export of all registered variables as a [JSON Schema], for validating config
+files before they reach the application.
+
+Each category is an object property, and each variable a typed property of its
+category with its `default`, `minimum`, `maximum` and `description` where
+known. Integers with only a few allowed values also list them as an `enum`.
+Variables that wrap around their range have no `minimum` or `maximum`, as any
+value is wrapped into it. Derived variables are marked `readOnly`. Unknown categories and
+variables are not allowed.
+
+```json
+{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "type": "object",
+  "properties": {
+    "render": {
+      "type": "object",
+      "properties": {
+        "gamma": { "type": "number", "default": 2.2, "minimum": 1, "maximum": 3 }
+      },
+      "additionalProperties": false
+    }
+  },
+  "additionalProperties": false
+}
+```
+
+[JSON Schema]: https://json-schema.org/
+*/
+
+use core::fmt::{Display, Write};
+
+use crate::{all, compat::prelude::*, Descriptor, RangeMode, Tuneable};
+
+fn string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// A JSON object being written, tracking whether a separator is needed
+struct Object<'a> {
+    out: &'a mut String,
+    empty: bool,
+}
+
+impl<'a> Object<'a> {
+    fn new(out: &'a mut String) -> Self {
+        out.push('{');
+        Self { out, empty: true }
+    }
+
+    fn key(&mut self, key: &str) -> &mut String {
+        if !self.empty {
+            self.out.push(',');
+        }
+        self.empty = false;
+
+        string(self.out, key);
+        self.out.push(':');
+        self.out
+    }
+
+    fn raw(&mut self, key: &str, value: &str) {
+        self.key(key).push_str(value);
+    }
+
+    fn string(&mut self, key: &str, value: &str) {
+        string(self.key(key), value);
+    }
+
+    fn number<T: Display>(&mut self, key: &str, value: Option<T>) {
+        if let Some(value) = value {
+            let value = format!("{}", value);
+            // Infinities and NaN have no JSON representation
+            if value.parse::<f64>().is_ok_and(f64::is_finite) {
+                self.raw(key, &value);
+            }
+        }
+    }
+
+    /// The limits of a variable. Wrapping variables accept any value and wrap
+    /// it into their range, so they have none.
+    fn range<T: Display>(&mut self, mode: RangeMode, min: Option<T>, max: Option<T>) {
+        if mode == RangeMode::Clamp {
+            self.number("minimum", min);
+            self.number("maximum", max);
+        }
+    }
+
+    /// List every value of an integer range as an `enum`, so editors can
+    /// offer them as a choice. Only done for small ranges.
+    fn values(&mut self, min: Option<i64>, max: Option<i64>) {
+        if let (Some(min), Some(max)) = (min, max) {
+            if max
+                .checked_sub(min)
+                .is_some_and(|span| (0..MAX_ENUM_VALUES).contains(&span))
+            {
+                let values = (min..=max).map(|v| format!("{}", v)).collect::<Vec<_>>();
+                self.raw("enum", &format!("[{}]", values.join(",")));
+            }
+        }
+    }
+
+    fn end(self) {
+        self.out.push('}');
+    }
+}
+
+/// Integer ranges with fewer values than this are exported as an `enum`
+const MAX_ENUM_VALUES: i64 = 16;
+
+fn variable(out: &mut String, descriptor: &Descriptor) {
+    let mut object = Object::new(out);
+
+    match &descriptor.state {
+        Tuneable::Float32(v) => {
+            object.string("type", "number");
+            object.number("default", Some(v.default()));
+            object.range(v.mode(), v.min(), v.max());
+        }
+        Tuneable::Float64(v) => {
+            object.string("type", "number");
+            object.number("default", Some(v.default()));
+            object.range(v.mode(), v.min(), v.max());
+        }
+        Tuneable::Int32(v) => {
+            object.string("type", "integer");
+            object.number("default", Some(v.default()));
+            object.range(v.mode(), v.min(), v.max());
+            if v.mode() == RangeMode::Clamp {
+                object.values(v.min().map(i64::from), v.max().map(i64::from));
+            }
+        }
+        Tuneable::Int64(v) => {
+            object.string("type", "integer");
+            object.number("default", Some(v.default()));
+            object.range(v.mode(), v.min(), v.max());
+            if v.mode() == RangeMode::Clamp {
+                object.values(v.min(), v.max());
+            }
+        }
+        Tuneable::Uint(_) | Tuneable::Size(_) => {
+            object.string("type", "integer");
+            object.number("minimum", Some(0));
+        }
+        Tuneable::Boolean(v) => {
+            object.string("type", "boolean");
+            object.raw("default", if v.default { "true" } else { "false" });
+        }
+        Tuneable::Derived(_) => {
+            object.string("type", "number");
+            object.raw("readOnly", "true");
+        }
+    }
+
+    if let Some(description) = &descriptor.description {
+        object.string("description", description);
+    }
+
+    object.end();
+}
+
+/// Produce a JSON Schema describing every registered variable
+pub fn export() -> String {
+    let mut out = String::new();
+    let mut root = Object::new(&mut out);
+    root.string("$schema", "http://json-schema.org/draft-07/schema#");
+    root.string("type", "object");
+
+    let properties = root.key("properties");
+    let mut categories: Vec<(String, Vec<Descriptor>)> = vec![];
+    for descriptor in all() {
+        match categories.last_mut() {
+            Some((category, vars)) if *category == descriptor.category => vars.push(descriptor),
+            _ => categories.push((descriptor.category.clone(), vec![descriptor])),
+        }
+    }
+
+    let mut groups = Object::new(properties);
+    for (category, descriptors) in categories {
+        let mut group = Object::new(groups.key(&category));
+        group.string("type", "object");
+
+        let mut vars = Object::new(group.key("properties"));
+        for descriptor in descriptors {
+            variable(vars.key(&descriptor.name), &descriptor);
+        }
+        vars.end();
+
+        group.raw("additionalProperties", "false");
+        group.end();
+    }
+    groups.end();
+
+    root.raw("additionalProperties", "false");
+    root.end();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::export;
+    use crate::{Boolean, Float32, Int32, Int64};
+    use serial_test::serial;
+
+    const TEST_GAMMA: Float32 = Float32::new("schema", "gamma", 2.2, Some(1.0), Some(3.0))
+        .with_description("Display \"gamma\"");
+    const TEST_SEED: Int64 = Int64::new("schema", "seed", 7, None, None);
+    const TEST_SHADOWS: Boolean = Boolean::new("schema", "shadows", true);
+    const TEST_QUALITY: Int32 = Int32::new("schema", "quality", 2, Some(1), Some(4));
+    const TEST_YAW: Float32 = Float32::new("schema", "yaw", 0.0, Some(0.0), Some(360.0)).wrapping();
+
+    #[test]
+    #[serial]
+    fn export_schema() {
+        TEST_GAMMA.register();
+        TEST_SEED.register();
+        TEST_SHADOWS.register();
+        TEST_QUALITY.register();
+        TEST_YAW.register();
+
+        let schema = export();
+        assert!(schema.starts_with(
+            r#"{"$schema":"http://json-schema.org/draft-07/schema#","type":"object","properties":{"#
+        ));
+        assert!(schema.contains(
+            r#""schema":{"type":"object","properties":{"gamma":{"type":"number","default":2.2,"minimum":1,"maximum":3,"description":"Display \"gamma\""},"quality":{"type":"integer","default":2,"minimum":1,"maximum":4,"enum":[1,2,3,4]},"seed":{"type":"integer","default":7},"shadows":{"type":"boolean","default":true},"yaw":{"type":"number","default":0}},"additionalProperties":false}"#
+        ));
+        assert!(schema.ends_with(r#"},"additionalProperties":false}"#));
+    }
+}
@@ -0,0 +1,331 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Finding variables by pattern rather than exact name.
+
+Globs are matched against the full `category.name`: `*` matches any run of
+characters, including none, and `?` matches exactly one. Fuzzy search ranks
+names that contain the characters of the query in order, preferring
+consecutive characters and the start of words.
+*/
+
+use crate::{
+    compat::prelude::*, transaction, AsTuneable, Boolean, ChangeSource, Float32, Float64, Int32,
+    Int64, SetError, TunaState, Tuneable, TUNA_STATE,
+};
+
+/// Check if a glob matches the text
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Where to resume after the last `*`, if the rest fails to match
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Score a match of the query against the text, taking the first occurrence of
+/// each character after `start`.
+fn greedy(query: &str, text: &str, start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut chars = text.char_indices().skip_while(|(i, _)| *i < start);
+
+    for q in query.chars().map(|c| c.to_ascii_lowercase()) {
+        let (index, _) = chars.find(|(_, c)| c.to_ascii_lowercase() == q)?;
+
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 4;
+        }
+        if index == 0 || matches!(text.as_bytes()[index - 1], b'.' | b'_' | b'-') {
+            score += 3;
+        }
+
+        previous = Some(index);
+    }
+
+    Some(score)
+}
+
+/// Score how well the query fuzzily matches the text, or `None` if the
+/// characters of the query don't all appear in order. Tries every place the
+/// match can start, so a prefix shared by many names doesn't hide a better
+/// match later on.
+fn fuzzy(query: &str, text: &str) -> Option<i64> {
+    let first = query.chars().next()?.to_ascii_lowercase();
+    let score = text
+        .char_indices()
+        .filter(|(_, c)| c.to_ascii_lowercase() == first)
+        .filter_map(|(start, _)| greedy(query, text, start))
+        .max()?;
+
+    // Prefer shorter names when the match is otherwise equal
+    Some(score * 256 - text.len() as i64)
+}
+
+/// All variables whose `category.name` matches the glob, sorted
+pub fn matching(pattern: &str) -> Vec<(String, String)> {
+    matching_locked(&TUNA_STATE.read(), pattern)
+}
+
+/// Like [`matching`], for callers that already hold the lock. Taking the read
+/// lock again could deadlock if a writer is waiting.
+pub(crate) fn matching_locked(tuna: &TunaState, pattern: &str) -> Vec<(String, String)> {
+    let mut matches = tuna
+        .iter()
+        .flat_map(|(category, group)| {
+            group
+                .keys()
+                .filter(move |name| {
+                    glob(
+                        pattern.as_bytes(),
+                        format!("{}.{}", category, name).as_bytes(),
+                    )
+                })
+                .map(move |name| (category.clone(), name.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    matches.sort();
+    matches
+}
+
+/// All variables whose `category.name` fuzzily matches the query, best match
+/// first. Queries containing `*` or `?` are treated as globs instead, and an
+/// empty query matches everything.
+pub fn search(query: &str) -> Vec<(String, String)> {
+    if query.is_empty() {
+        return matching("*");
+    }
+
+    if query.contains(['*', '?']) {
+        return matching(query);
+    }
+
+    let tuna = TUNA_STATE.read();
+    let mut scored = tuna
+        .iter()
+        .flat_map(|(category, group)| {
+            group.keys().filter_map(move |name| {
+                let score = fuzzy(query, &format!("{}.{}", category, name))?;
+                Some((score, category.clone(), name.clone()))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| (&a.1, &a.2).cmp(&(&b.1, &b.2))));
+    scored
+        .into_iter()
+        .map(|(_, category, name)| (category, name))
+        .collect()
+}
+
+/// Values that can be written to many variables at once with
+/// [`set_matching`]
+pub trait MatchValue: Copy {
+    /// The type of variable the value is written to
+    type Tuneable: AsTuneable<Result = Self>;
+}
+
+impl MatchValue for f32 {
+    type Tuneable = Float32;
+}
+
+impl MatchValue for f64 {
+    type Tuneable = Float64;
+}
+
+impl MatchValue for i32 {
+    type Tuneable = Int32;
+}
+
+impl MatchValue for i64 {
+    type Tuneable = Int64;
+}
+
+impl MatchValue for bool {
+    type Tuneable = Boolean;
+}
+
+/// Set every variable matching the glob that has the same type as the value,
+/// e.g. `set_matching("debug.*", false)`. The writes are applied as one
+/// [`transaction`](crate::transaction), so either all of them succeed or none
+/// do. Returns the variables that were written.
+pub fn set_matching<V: MatchValue>(
+    pattern: &str,
+    value: V,
+) -> Result<Vec<(String, String)>, SetError> {
+    let targets = {
+        let tuna = TUNA_STATE.read();
+        matching_locked(&tuna, pattern)
+            .into_iter()
+            .filter(|(category, name)| {
+                tuna.get(category)
+                    .and_then(|group| group.get(name))
+                    .and_then(V::Tuneable::from_tuneable)
+                    .is_some()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    transaction(|tx| {
+        for (category, name) in &targets {
+            tx.set::<V::Tuneable>(category, name, value);
+        }
+    })?;
+
+    Ok(targets)
+}
+
+/// Reset every variable matching the glob to its default value. Like
+/// [`set_matching`], the defaults are written as one
+/// [`transaction`](crate::transaction) that runs validators and checks
+/// constraints, so either all variables are reset or none are. Returns the
+/// variables that were reset.
+pub fn reset_matching(pattern: &str) -> Result<Vec<(String, String)>, SetError> {
+    reset_matching_from(pattern, ChangeSource::Code)
+}
+
+/// Like [`reset_matching`], attributing the writes to `source` instead of
+/// [`ChangeSource::Code`]
+pub fn reset_matching_from(
+    pattern: &str,
+    source: ChangeSource,
+) -> Result<Vec<(String, String)>, SetError> {
+    let defaults = {
+        let tuna = TUNA_STATE.read();
+        matching_locked(&tuna, pattern)
+            .into_iter()
+            .filter_map(|(category, name)| {
+                let tuneable = tuna.get(&category)?.get(&name)?.clone();
+                Some((category, name, tuneable))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut targets = vec![];
+    transaction(|tx| {
+        tx.source(source);
+        for (category, name, tuneable) in &defaults {
+            match tuneable {
+                Tuneable::Float32(v) => tx.set::<Float32>(category, name, v.default()),
+                Tuneable::Float64(v) => tx.set::<Float64>(category, name, v.default()),
+                Tuneable::Int32(v) => tx.set::<Int32>(category, name, v.default()),
+                Tuneable::Int64(v) => tx.set::<Int64>(category, name, v.default()),
+                Tuneable::Boolean(v) => tx.set::<Boolean>(category, name, v.default),
+                _ => continue,
+            };
+
+            targets.push((category.clone(), name.clone()));
+        }
+    })?;
+
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob, matching, reset_matching, search, set_matching};
+    use crate::compat::prelude::*;
+    use crate::{Boolean, Float32, SetError};
+    use serial_test::serial;
+
+    const TEST_SHADOWS: Boolean = Boolean::new("search_render", "shadows_enabled", true);
+    const TEST_FOG: Boolean = Boolean::new("search_render", "fog_enabled", true);
+    const TEST_GAMMA: Float32 = Float32::new("search_render", "gamma", 2.2, None, None);
+    const TEST_DEBUG: Boolean = Boolean::new("search_debug", "debug_draw", false);
+
+    #[test]
+    fn globs() {
+        assert!(glob(b"render.*.enabled", b"render.shadows.enabled"));
+        assert!(glob(b"*.debug_*", b"physics.debug_draw"));
+        assert!(glob(b"*", b""));
+        assert!(glob(b"a?c", b"abc"));
+        assert!(!glob(b"a?c", b"ac"));
+        assert!(!glob(b"render.*", b"physics.gravity"));
+        assert!(glob(b"*a*b", b"xaxxab"));
+    }
+
+    #[test]
+    #[serial]
+    fn bulk() {
+        TEST_SHADOWS.register();
+        TEST_FOG.register();
+        TEST_GAMMA.register();
+        TEST_DEBUG.register();
+
+        assert_eq!(
+            matching("search_render.*_enabled"),
+            vec![
+                ("search_render".to_owned(), "fog_enabled".to_owned()),
+                ("search_render".to_owned(), "shadows_enabled".to_owned()),
+            ]
+        );
+
+        let written = set_matching("search_render.*", false).unwrap();
+        assert_eq!(written.len(), 2);
+        assert!(!TEST_SHADOWS.read());
+        assert!(!TEST_FOG.read());
+        assert_eq!(TEST_GAMMA.read(), 2.2);
+
+        // Resetting checks constraints like any other write
+        crate::add_constraint(crate::Constraint::mutually_exclusive(&[
+            ("search_render", "shadows_enabled"),
+            ("search_render", "fog_enabled"),
+        ]));
+        assert!(matches!(
+            reset_matching("search_render.*"),
+            Err(SetError::Constraint(_))
+        ));
+        assert!(!TEST_SHADOWS.read());
+        crate::clear_constraints();
+
+        assert_eq!(reset_matching("search_render.*").unwrap().len(), 3);
+        assert!(TEST_SHADOWS.read());
+        assert!(TEST_FOG.read());
+    }
+
+    #[test]
+    #[serial]
+    fn fuzzy_ranking() {
+        TEST_SHADOWS.register();
+        TEST_FOG.register();
+        TEST_DEBUG.register();
+
+        let results = search("shen");
+        assert_eq!(
+            results.first(),
+            Some(&("search_render".to_owned(), "shadows_enabled".to_owned()))
+        );
+
+        let results = search("dbgdraw");
+        assert_eq!(
+            results.first(),
+            Some(&("search_debug".to_owned(), "debug_draw".to_owned()))
+        );
+        assert!(search("zzz").is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Immutable views of the whole state, for reading many variables consistently
+without taking the lock for each read.
+
+Snapshots are copy-on-write per category: taking a new one only copies the
+categories that changed since the previous one, and shares the rest.
+*/
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{
+    compat::{prelude::*, Lazy, Map, Mutex},
+    AsTuneable, Tuneable, TUNA_STATE,
+};
+
+/// The state captured by a [`Snapshot`]. Like [`TunaState`](crate::TunaState),
+/// but each category is shared with other snapshots until it changes.
+pub type SharedState = Map<String, Arc<Map<String, Tuneable>>>;
+
+/// Bumped on every change to the state, while the write lock is held.
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+
+/// Categories changed since the latest snapshot was taken
+static DIRTY: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Default::default()));
+
+static LATEST: Lazy<Mutex<Option<Snapshot>>> = Lazy::new(|| Mutex::new(None));
+
+/// Record that the given variables changed. Must be called while the state is
+/// write-locked.
+pub(crate) fn bump_epoch(changed: &[(String, String)]) {
+    let mut dirty = DIRTY.lock();
+    for (category, _) in changed {
+        if !dirty.contains(category) {
+            dirty.push(category.clone());
+        }
+    }
+
+    EPOCH.fetch_add(1, Ordering::Release);
+}
+
+/// A consistent, read-only copy of all variables at one point in time
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    state: Arc<SharedState>,
+    epoch: u64,
+}
+
+impl Snapshot {
+    /// Get the value of a variable as it was when the snapshot was taken, if
+    /// it matches the expected type
+    pub fn get<T: AsTuneable>(&self, category: &str, name: &str) -> Option<T::Result> {
+        #[cfg(feature = "std")]
+        if let Some(value) = crate::overrides::get::<T>(category, name) {
+            return Some(value);
+        }
+
+        let res = self
+            .state
+            .get(category)
+            .and_then(|group| group.get(name))
+            .and_then(|value| T::from_tuneable(value));
+
+        #[cfg(feature = "stats")]
+        if res.is_some() {
+            crate::stats::record_read(category, name);
+        }
+
+        res
+    }
+
+    /// The full state captured by the snapshot
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    /// Counts changes to the state; two snapshots with the same epoch are
+    /// identical.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Snapshot {
+    /// Serializes the same way as the captured [`TunaState`](crate::TunaState)
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.state.len()))?;
+        for (category, group) in self.state.iter() {
+            map.serialize_entry(category, &**group)?;
+        }
+        map.end()
+    }
+}
+
+/// Capture the current state. Call this once per frame and read through the
+/// snapshot with e.g. [`Float32::read_in`](crate::Float32::read_in). The copy
+/// is shared until something changes, so calling this when nothing has been
+/// written since the last call is cheap, and otherwise only the categories
+/// that changed are copied.
+pub fn frame() -> Snapshot {
+    let mut latest = LATEST.lock();
+
+    if let Some(snapshot) = latest.as_ref() {
+        if snapshot.epoch == EPOCH.load(Ordering::Acquire) as u64 {
+            return snapshot.clone();
+        }
+    }
+
+    let snapshot = {
+        let tuna = TUNA_STATE.read();
+        // Writers mark categories dirty while holding the write lock, so
+        // nothing can change between taking the list and copying
+        let dirty = core::mem::take(&mut *DIRTY.lock());
+
+        let state = tuna
+            .iter()
+            .map(|(category, group)| {
+                let shared = latest
+                    .as_ref()
+                    .filter(|_| !dirty.contains(category))
+                    .and_then(|previous| previous.state.get(category))
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(group.clone()));
+                (category.clone(), shared)
+            })
+            .collect();
+
+        Snapshot {
+            epoch: EPOCH.load(Ordering::Acquire) as u64,
+            state: Arc::new(state),
+        }
+    };
+
+    *latest = Some(snapshot.clone());
+    snapshot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::frame;
+    use crate::{Boolean, Float32};
+    use alloc::sync::Arc;
+    use serial_test::serial;
+
+    const TEST_FLOAT: Float32 = Float32::new("snapshot", "float", 1.0, None, None);
+    const TEST_BOOL: Boolean = Boolean::new("snapshot", "bool", false);
+
+    #[test]
+    #[serial]
+    fn consistent() {
+        TEST_FLOAT.register();
+        TEST_BOOL.register();
+        TEST_FLOAT.reset();
+        TEST_BOOL.reset();
+
+        let view = frame();
+        TEST_FLOAT.write(2.0);
+        TEST_BOOL.write(true);
+
+        assert_eq!(TEST_FLOAT.read_in(&view), 1.0);
+        assert!(!TEST_BOOL.read_in(&view));
+
+        let view = frame();
+        assert_eq!(TEST_FLOAT.read_in(&view), 2.0);
+        assert!(TEST_BOOL.read_in(&view));
+    }
+
+    #[test]
+    #[serial]
+    fn shared_until_changed() {
+        TEST_FLOAT.register();
+
+        let first = frame();
+        let second = frame();
+        assert_eq!(first.epoch(), second.epoch());
+
+        TEST_FLOAT.write(3.0);
+        let third = frame();
+        assert!(third.epoch() > second.epoch());
+    }
+
+    #[test]
+    #[serial]
+    fn copy_on_write() {
+        const TEST_OTHER: Float32 = Float32::new("snapshot_other", "float", 1.0, None, None);
+        TEST_FLOAT.register();
+        TEST_OTHER.register();
+
+        let first = frame();
+        TEST_FLOAT.write(4.0);
+        let second = frame();
+
+        assert!(!Arc::ptr_eq(
+            &first.state()["snapshot"],
+            &second.state()["snapshot"]
+        ));
+        assert!(Arc::ptr_eq(
+            &first.state()["snapshot_other"],
+            &second.state()["snapshot_other"]
+        ));
+        assert_eq!(TEST_FLOAT.read_in(&second), 4.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    #[serial]
+    fn serialize() {
+        const TEST_BOUNDED: Float32 =
+            Float32::new("snapshot_serde", "bounded", 0.5, Some(0.0), None);
+        TEST_BOUNDED.register();
+
+        let json = serde_json::to_value(frame()).unwrap();
+        assert_eq!(
+            json["snapshot_serde"]["bounded"],
+            serde_json::json!({
+                "Float32": {
+                    "default": 0.5,
+                    "min": 0.0,
+                    "max": null,
+                    "soft_min": null,
+                    "soft_max": null,
+                    "mode": "Clamp",
+                    "current": 0.5
+                }
+            })
+        );
+
+        let state: crate::TunaState = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            state["snapshot_serde"]["bounded"],
+            crate::Tuneable::Float32(_)
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn unregistered() {
+        const MISSING: Float32 = Float32::new("snapshot", "missing", 4.0, None, None);
+
+        assert_eq!(MISSING.read_in(&frame()), 4.0);
+    }
+}
@@ -0,0 +1,160 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Attribution of writes to where they came from, so an unexpected change can be
+traced back to the file watcher, a web client or the application itself.
+
+Every write path takes a [`ChangeSource`], defaulting to [`ChangeSource::Code`]
+for the plain `set`, `write` and `reset` functions. The source of the last
+write to each variable is recorded while the state is still locked, so it
+always matches the stored value, and is passed to change listeners.
+
+The sources live beside [`TunaState`](crate::TunaState) so its serialized
+formats stay unchanged.
+*/
+
+use core::fmt;
+
+use crate::compat::{prelude::*, Lazy, Map, Mutex};
+
+/// Where a write came from
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeSource {
+    /// The application itself, through `set`, `write`, `reset` and friends
+    #[default]
+    Code,
+    /// A config file, with its path
+    File(String),
+    /// A `tuna-web` client, with its address
+    Web(String),
+    /// An in-game console or similar command interface
+    Console,
+    /// An environment variable, see `apply_env`
+    Env,
+    /// A command line argument, see the `args` module
+    Args,
+    /// A recording being replayed, see the `replay` module
+    Replay,
+    /// An animator advanced by `tick`
+    Animation,
+}
+
+impl fmt::Display for ChangeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Code => write!(f, "code"),
+            Self::File(path) => write!(f, "file {}", path),
+            Self::Web(peer) => write!(f, "web client {}", peer),
+            Self::Console => write!(f, "console"),
+            Self::Env => write!(f, "environment"),
+            Self::Args => write!(f, "command line"),
+            Self::Replay => write!(f, "replay"),
+            Self::Animation => write!(f, "animation"),
+        }
+    }
+}
+
+static SOURCES: Lazy<Mutex<Map<String, Map<String, ChangeSource>>>> =
+    Lazy::new(|| Mutex::new(Default::default()));
+
+/// Attribute the changed variables to a source. Must be called while the
+/// state is write-locked.
+pub(crate) fn record(changed: &[(String, String)], source: &ChangeSource) {
+    let mut sources = SOURCES.lock();
+
+    for (category, name) in changed {
+        sources
+            .entry(category.clone())
+            .or_default()
+            .insert(name.clone(), source.clone());
+    }
+}
+
+/// What last wrote a variable. Variables that were never written after being
+/// registered have no source.
+pub fn last_source(category: &str, name: &str) -> Option<ChangeSource> {
+    SOURCES
+        .lock()
+        .get(category)
+        .and_then(|group| group.get(name))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{last_source, ChangeSource};
+    use crate::compat::prelude::*;
+    use crate::{
+        add_listener, remove_listener, reset_from, reset_matching_from, set_from, set_staging,
+        stage_from, transaction, Float32, Int32,
+    };
+    use serial_test::serial;
+    use std::sync::{Arc, Mutex};
+
+    const TEST_SPEED: Float32 = Float32::new("source", "speed", 1.0, None, None);
+    const TEST_LIVES: Int32 = Int32::new("source", "lives", 3, None, None);
+
+    #[test]
+    #[serial]
+    fn attribution() {
+        TEST_SPEED.register();
+        TEST_LIVES.register();
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let sink = seen.clone();
+        let listener = add_listener(move |category, _, _, source| {
+            if category == "source" {
+                sink.lock().unwrap().push(source.clone());
+            }
+        });
+
+        TEST_SPEED.write(2.0);
+        assert_eq!(last_source("source", "speed"), Some(ChangeSource::Code));
+
+        let console = ChangeSource::Console;
+        assert!(set_from::<Float32>("source", "speed", 3.0, console.clone()));
+        assert_eq!(last_source("source", "speed"), Some(console));
+
+        let file = ChangeSource::File("tuna.toml".to_owned());
+        let web = ChangeSource::Web("127.0.0.1:5000".to_owned());
+        set_staging(true);
+        stage_from::<Float32>("source", "speed", 4.0, file.clone()).unwrap();
+        stage_from::<Int32>("source", "lives", 5, web.clone()).unwrap();
+        set_staging(false);
+        assert_eq!(last_source("source", "speed"), Some(file.clone()));
+        assert_eq!(last_source("source", "lives"), Some(web.clone()));
+
+        transaction(|tx| {
+            tx.source(ChangeSource::Replay)
+                .set::<Int32>("source", "lives", 6);
+        })
+        .unwrap();
+        assert_eq!(last_source("source", "lives"), Some(ChangeSource::Replay));
+
+        remove_listener(listener);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                ChangeSource::Code,
+                ChangeSource::Console,
+                file,
+                web.clone(),
+                ChangeSource::Replay
+            ]
+        );
+        assert_eq!(
+            crate::describe("source", "lives").unwrap().last_source,
+            Some(ChangeSource::Replay)
+        );
+
+        reset_from::<Float32>("source", "speed", ChangeSource::Console);
+        assert_eq!(last_source("source", "speed"), Some(ChangeSource::Console));
+
+        reset_matching_from("source.*", web.clone()).unwrap();
+        assert_eq!(last_source("source", "speed"), Some(web.clone()));
+        assert_eq!(last_source("source", "lives"), Some(web));
+    }
+}
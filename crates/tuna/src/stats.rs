@@ -0,0 +1,147 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Per-variable access counters, for finding variables nobody reads anymore and
+the ones read the most. Only compiled with the `stats` feature, as every read
+takes a lock to count it.
+
+Reads through [`crate::get`] and [`crate::Snapshot::get`] are counted, which
+covers `read` and `read_in` on all definitions. Writes are counted once per
+variable for every successful write, reset or transaction.
+*/
+
+use std::time::SystemTime;
+
+use crate::{
+    all,
+    compat::{Lazy, Map, Mutex},
+    source, ChangeSource,
+};
+
+/// How a variable has been used since startup, or since [`clear_stats`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub reads: u64,
+    pub last_read: Option<SystemTime>,
+    pub writes: u64,
+    pub last_write: Option<SystemTime>,
+    /// What last wrote the variable, see [`ChangeSource`]
+    pub last_writer: Option<ChangeSource>,
+}
+
+static STATS: Lazy<Mutex<Map<String, Map<String, Stats>>>> =
+    Lazy::new(|| Mutex::new(Default::default()));
+
+fn update(category: &str, name: &str, f: impl FnOnce(&mut Stats)) {
+    let mut stats = STATS.lock();
+
+    // Avoid allocating the keys for every read of a known variable
+    if let Some(entry) = stats.get_mut(category).and_then(|g| g.get_mut(name)) {
+        return f(entry);
+    }
+
+    f(stats
+        .entry(category.to_owned())
+        .or_default()
+        .entry(name.to_owned())
+        .or_default());
+}
+
+pub(crate) fn record_read(category: &str, name: &str) {
+    update(category, name, |stats| {
+        stats.reads += 1;
+        stats.last_read = Some(SystemTime::now());
+    });
+}
+
+/// Count writes to the changed variables. Their sources must already have
+/// been recorded.
+pub(crate) fn record_writes(changed: &[(String, String)]) {
+    let now = SystemTime::now();
+
+    for (category, name) in changed {
+        let writer = source::last_source(category, name);
+        update(category, name, |stats| {
+            stats.writes += 1;
+            stats.last_write = Some(now);
+            stats.last_writer = writer;
+        });
+    }
+}
+
+/// The usage of a single variable. Registered variables that were never used
+/// have empty stats.
+pub fn stats(category: &str, name: &str) -> Stats {
+    STATS
+        .lock()
+        .get(category)
+        .and_then(|group| group.get(name))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// All registered variables that haven't been read, sorted by category and
+/// name
+pub fn never_read() -> Vec<(String, String)> {
+    all()
+        .into_iter()
+        .filter(|descriptor| descriptor.stats.reads == 0)
+        .map(|descriptor| (descriptor.category, descriptor.name))
+        .collect()
+}
+
+/// Forget all counts, e.g. after loading so only reads during play count
+pub fn clear_stats() {
+    STATS.lock().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_stats, never_read, stats};
+    use crate::{ChangeSource, Float32, Int32};
+    use serial_test::serial;
+
+    const TEST_HOT: Float32 = Float32::new("stats", "hot", 1.0, None, None);
+    const TEST_DEAD: Int32 = Int32::new("stats", "dead", 1, None, None);
+
+    #[test]
+    #[serial]
+    fn counts() {
+        TEST_HOT.register();
+        TEST_DEAD.register();
+        clear_stats();
+
+        for _ in 0..3 {
+            TEST_HOT.read();
+        }
+        TEST_HOT.read_in(&crate::frame());
+
+        assert!(crate::set_from::<Int32>(
+            "stats",
+            "dead",
+            2,
+            ChangeSource::Web("127.0.0.1:5000".to_owned())
+        ));
+
+        let hot = stats("stats", "hot");
+        assert_eq!(hot.reads, 4);
+        assert!(hot.last_read.is_some());
+        assert_eq!(hot.writes, 0);
+
+        let dead = stats("stats", "dead");
+        assert_eq!(dead.reads, 0);
+        assert_eq!(dead.writes, 1);
+        assert_eq!(
+            dead.last_writer,
+            Some(ChangeSource::Web("127.0.0.1:5000".to_owned()))
+        );
+
+        let unread = never_read();
+        assert!(unread.contains(&("stats".to_owned(), "dead".to_owned())));
+        assert!(!unread.contains(&("stats".to_owned(), "hot".to_owned())));
+
+        assert_eq!(crate::describe("stats", "hot").unwrap().stats.reads, 4);
+    }
+}
@@ -0,0 +1,440 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Parameter sweeps and random search, for running the application headless over
+many configurations of its variables and scoring each run.
+
+A [`Sweep`] is built from a [`Strategy`] and the variables to vary, each with
+a range. Every configuration is applied with [`crate::set`] semantics before
+the scoring function is called, and the original values are restored when the
+sweep is done. Runs hold the values as stored, after integers are rounded and
+values clamped to their limits. The [`Results`] can be written out as CSV,
+with one column per variable and a final `score` column.
+
+```no_run
+use tuna::sweep::{Strategy, Sweep};
+
+let results = Sweep::new(Strategy::LatinHypercube { samples: 32, seed: 7 })
+    .parameter("physics", "gravity", 5.0, 15.0, 0)
+    .parameter("physics", "substeps", 1.0, 8.0, 0)
+    .run(|_| {
+        // run the simulation and measure something
+        0.0
+    })
+    .unwrap();
+
+results.save_csv("sweep.csv").unwrap();
+```
+*/
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    api::try_set_f64, rng::Rng, transaction, Boolean, ChangeSource, Float32, Float64, Int32, Int64,
+    SetError, Tuneable, TUNA_STATE,
+};
+
+/// How configurations are chosen
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Strategy {
+    /// Every combination of the parameters' steps
+    Grid,
+    /// Independent uniform samples within each range
+    Random { samples: usize, seed: u64 },
+    /// Samples which cover each range evenly, with exactly one sample in each
+    /// of `samples` equal strata per parameter
+    LatinHypercube { samples: usize, seed: u64 },
+}
+
+/// A variable to vary, and the range to vary it over
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameter {
+    pub category: String,
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+    /// Number of evenly spaced values, including both ends, used by
+    /// [`Strategy::Grid`]
+    pub steps: usize,
+}
+
+impl Parameter {
+    fn at(&self, t: f64) -> f64 {
+        self.min + (self.max - self.min) * t
+    }
+
+    fn grid(&self) -> Vec<f64> {
+        match self.steps {
+            0 | 1 => vec![self.min],
+            steps => (0..steps)
+                .map(|i| self.at(i as f64 / (steps - 1) as f64))
+                .collect(),
+        }
+    }
+}
+
+/// A sweep over a set of parameters
+#[derive(Clone, Debug)]
+pub struct Sweep {
+    strategy: Strategy,
+    parameters: Vec<Parameter>,
+}
+
+/// A single scored configuration
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    /// Values as stored, in the same order as the sweep's parameters
+    pub values: Vec<f64>,
+    pub score: f64,
+}
+
+/// The outcome of a sweep
+#[derive(Clone, Debug, PartialEq)]
+pub struct Results {
+    pub parameters: Vec<Parameter>,
+    pub runs: Vec<Run>,
+}
+
+impl Sweep {
+    /// Create a sweep without parameters, add them with [`Sweep::parameter`]
+    pub fn new(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            parameters: vec![],
+        }
+    }
+
+    /// Add a variable to vary between `min` and `max`. `steps` is only used
+    /// for grid sweeps.
+    pub fn parameter(
+        mut self,
+        category: &str,
+        name: &str,
+        min: f64,
+        max: f64,
+        steps: usize,
+    ) -> Self {
+        self.parameters.push(Parameter {
+            category: category.to_owned(),
+            name: name.to_owned(),
+            min,
+            max,
+            steps,
+        });
+        self
+    }
+
+    /// The parameters being varied
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    /// All configurations the sweep will run, with values in parameter order
+    pub fn configurations(&self) -> Vec<Vec<f64>> {
+        if self.parameters.is_empty() {
+            return vec![];
+        }
+
+        match self.strategy {
+            Strategy::Grid => {
+                let mut configurations = vec![vec![]];
+                for parameter in &self.parameters {
+                    let values = parameter.grid();
+                    configurations = configurations
+                        .into_iter()
+                        .flat_map(|prefix: Vec<f64>| {
+                            values.iter().map(move |v| {
+                                let mut next = prefix.clone();
+                                next.push(*v);
+                                next
+                            })
+                        })
+                        .collect();
+                }
+                configurations
+            }
+            Strategy::Random { samples, seed } => {
+                let mut rng = Rng::new(seed);
+                (0..samples)
+                    .map(|_| {
+                        self.parameters
+                            .iter()
+                            .map(|p| p.at(rng.next_f64()))
+                            .collect()
+                    })
+                    .collect()
+            }
+            Strategy::LatinHypercube { samples, seed } => {
+                let mut rng = Rng::new(seed);
+                let mut configurations = vec![Vec::with_capacity(self.parameters.len()); samples];
+                for parameter in &self.parameters {
+                    // Shuffle the strata so each parameter pairs them up
+                    // differently
+                    let mut strata = (0..samples).collect::<Vec<_>>();
+                    for i in (1..samples).rev() {
+                        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                        strata.swap(i, j);
+                    }
+
+                    for (configuration, stratum) in configurations.iter_mut().zip(strata) {
+                        let t = (stratum as f64 + rng.next_f64()) / samples as f64;
+                        configuration.push(parameter.at(t));
+                    }
+                }
+                configurations
+            }
+        }
+    }
+
+    /// Apply each configuration in turn and call `score` with the values as
+    /// stored. Variables are restored to their previous values afterwards,
+    /// also when a configuration fails to apply.
+    pub fn run(&self, mut score: impl FnMut(&[f64]) -> f64) -> Result<Results, SetError> {
+        let previous = {
+            let tuna = TUNA_STATE.read();
+            self.parameters
+                .iter()
+                .map(|p| {
+                    tuna.get(&p.category)
+                        .and_then(|group| group.get(&p.name))
+                        .cloned()
+                        .ok_or(SetError::NotFound)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut runs = vec![];
+        let mut result = Ok(());
+        for configuration in self.configurations() {
+            let values = match self.apply(&configuration).and_then(|_| self.stored()) {
+                Ok(values) => values,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+
+            let score = score(&values);
+            runs.push(Run { values, score });
+        }
+
+        // Best effort; the variables were writable a moment ago
+        let _ = self.restore(&previous);
+
+        result.map(|_| Results {
+            parameters: self.parameters.clone(),
+            runs,
+        })
+    }
+
+    fn apply(&self, values: &[f64]) -> Result<(), SetError> {
+        for (parameter, value) in self.parameters.iter().zip(values) {
+            try_set_f64(
+                &parameter.category,
+                &parameter.name,
+                *value,
+                ChangeSource::Code,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The current values of the parameters
+    fn stored(&self) -> Result<Vec<f64>, SetError> {
+        let tuna = TUNA_STATE.read();
+        self.parameters
+            .iter()
+            .map(|p| {
+                tuna.get(&p.category)
+                    .and_then(|group| group.get(&p.name))
+                    .and_then(|v| v.as_f64())
+                    .ok_or(SetError::NotFound)
+            })
+            .collect()
+    }
+
+    /// Write back the previous state of every parameter. Goes through the
+    /// variables' own types, as `Int64` values may not fit an `f64`.
+    fn restore(&self, previous: &[Tuneable]) -> Result<(), SetError> {
+        transaction(|tx| {
+            for (parameter, tuneable) in self.parameters.iter().zip(previous) {
+                let (category, name) = (&parameter.category, &parameter.name);
+                match tuneable {
+                    Tuneable::Float32(v) => tx.set::<Float32>(category, name, v.current),
+                    Tuneable::Float64(v) => tx.set::<Float64>(category, name, v.current),
+                    Tuneable::Int32(v) => tx.set::<Int32>(category, name, v.current),
+                    Tuneable::Int64(v) => tx.set::<Int64>(category, name, v.current),
+                    Tuneable::Boolean(v) => tx.set::<Boolean>(category, name, v.current),
+                    _ => continue,
+                };
+            }
+        })
+    }
+}
+
+impl Results {
+    /// The highest scoring run
+    pub fn best(&self) -> Option<&Run> {
+        self.runs
+            .iter()
+            .filter(|r| !r.score.is_nan())
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+
+    /// Write a header of `category.name` columns followed by `score`, and one
+    /// row per run.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        for parameter in &self.parameters {
+            write!(writer, "{}.{},", parameter.category, parameter.name)?;
+        }
+        writeln!(writer, "score")?;
+
+        for run in &self.runs {
+            for value in &run.values {
+                write!(writer, "{},", value)?;
+            }
+            writeln!(writer, "{}", run.score)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Write the runs to a new file at `path`, see [`Results::write_csv`]
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_csv(BufWriter::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Strategy, Sweep};
+    use crate::{Boolean, Float32, Int32, Int64, SetError};
+    use serial_test::serial;
+
+    const TEST_GRAVITY: Float32 = Float32::new("sweep", "gravity", 9.81, Some(0.0), None);
+    const TEST_SUBSTEPS: Int32 = Int32::new("sweep", "substeps", 4, Some(1), None);
+    const TEST_DAMPING: Boolean = Boolean::new("sweep", "damping", false);
+
+    #[test]
+    #[serial]
+    fn grid() {
+        TEST_GRAVITY.register();
+        TEST_SUBSTEPS.register();
+        TEST_DAMPING.register();
+
+        let sweep = Sweep::new(Strategy::Grid)
+            .parameter("sweep", "gravity", 5.0, 15.0, 3)
+            .parameter("sweep", "substeps", 1.0, 2.0, 2)
+            .parameter("sweep", "damping", 0.0, 1.0, 2);
+
+        let results = sweep
+            .run(|_| {
+                TEST_GRAVITY.read() as f64
+                    * TEST_SUBSTEPS.read() as f64
+                    * if TEST_DAMPING.read() { 0.5 } else { 1.0 }
+            })
+            .unwrap();
+
+        assert_eq!(results.runs.len(), 12);
+        assert_eq!(results.runs[0].values, vec![5.0, 1.0, 0.0]);
+        assert_eq!(results.runs[0].score, 5.0);
+        assert_eq!(results.runs[1].score, 2.5);
+        assert_eq!(results.best().unwrap().values, vec![15.0, 2.0, 0.0]);
+
+        // Restored afterwards
+        assert_eq!(TEST_GRAVITY.read(), 9.81);
+        assert_eq!(TEST_SUBSTEPS.read(), 4);
+        assert!(!TEST_DAMPING.read());
+
+        let mut csv = vec![];
+        results.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("sweep.gravity,sweep.substeps,sweep.damping,score")
+        );
+        assert_eq!(lines.next(), Some("5,1,0,5"));
+        assert_eq!(lines.count(), 11);
+    }
+
+    #[test]
+    fn latin_hypercube() {
+        let samples = 10;
+        let sweep = Sweep::new(Strategy::LatinHypercube { samples, seed: 3 })
+            .parameter("sweep", "a", 0.0, 1.0, 0)
+            .parameter("sweep", "b", 0.0, 10.0, 0);
+
+        let configurations = sweep.configurations();
+        assert_eq!(configurations.len(), samples);
+
+        // Exactly one sample per stratum for every parameter
+        for (index, scale) in [(0, 1.0), (1, 10.0)] {
+            let mut strata = configurations
+                .iter()
+                .map(|c| (c[index] / scale * samples as f64) as usize)
+                .collect::<Vec<_>>();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..samples).collect::<Vec<_>>());
+        }
+
+        // Deterministic from the seed
+        assert_eq!(configurations, sweep.configurations());
+    }
+
+    #[test]
+    fn random() {
+        let sweep = Sweep::new(Strategy::Random {
+            samples: 50,
+            seed: 1,
+        })
+        .parameter("sweep", "a", -2.0, 2.0, 0);
+
+        let configurations = sweep.configurations();
+        assert_eq!(configurations.len(), 50);
+        assert!(configurations.iter().all(|c| (-2.0..2.0).contains(&c[0])));
+    }
+
+    const TEST_LEVEL: Int32 = Int32::new("sweep", "level", 1, Some(0), Some(3));
+    const TEST_SEED: Int64 = Int64::new("sweep", "seed", (1 << 53) + 1, None, None);
+
+    #[test]
+    #[serial]
+    fn stored_values() {
+        TEST_LEVEL.register();
+        TEST_SEED.register();
+
+        let results = Sweep::new(Strategy::Grid)
+            .parameter("sweep", "level", 2.5, 4.5, 2)
+            .parameter("sweep", "seed", 0.0, 0.0, 1)
+            .run(|values| values[0])
+            .unwrap();
+
+        // Rounded, then clamped to the maximum
+        assert_eq!(results.runs[0].values, vec![3.0, 0.0]);
+        assert_eq!(results.runs[1].values, vec![3.0, 0.0]);
+        assert_eq!(results.runs[0].score, 3.0);
+
+        // Restored exactly, beyond what an `f64` holds
+        assert_eq!(TEST_LEVEL.read(), 1);
+        assert_eq!(TEST_SEED.read(), (1 << 53) + 1);
+    }
+
+    #[test]
+    #[serial]
+    fn unknown_variable() {
+        TEST_GRAVITY.register();
+
+        let sweep = Sweep::new(Strategy::Grid)
+            .parameter("sweep", "gravity", 1.0, 2.0, 2)
+            .parameter("sweep", "missing", 0.0, 1.0, 2);
+
+        assert_eq!(sweep.run(|_| 0.0), Err(SetError::NotFound));
+    }
+}
@@ -0,0 +1,344 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Frame-consistent updates: staged external writes that only become visible on
+[`commit`], and transactions that apply several writes atomically.
+*/
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    api::{finish_write, set_locked, validated},
+    compat::{prelude::*, Lazy, Mutex},
+    constraint, listener, source, AsTuneable, ChangeSource, SetError, TunaState, Tuneable,
+    TUNA_STATE,
+};
+
+type PendingWrite =
+    Box<dyn FnOnce(&mut TunaState, bool) -> Result<Vec<(String, String)>, SetError> + Send>;
+
+fn pending<T: AsTuneable>(category: &str, name: &str, value: T::Result) -> PendingWrite {
+    let (category, name) = (category.to_owned(), name.to_owned());
+    Box::new(move |tuna, check_constraints| {
+        set_locked::<T>(tuna, &category, &name, value, check_constraints)
+    })
+}
+
+static STAGING: AtomicBool = AtomicBool::new(false);
+
+/// A queued write, with the variable it targets and what it came from
+type StagedWrite = (String, String, ChangeSource, PendingWrite);
+
+static STAGED: Lazy<Mutex<Vec<StagedWrite>>> = Lazy::new(|| Mutex::new(Default::default()));
+
+/// Enable or disable staging. While staging, writes made with [`stage`] (such
+/// as those from `tuna-web` and `tuna-file`) are queued until [`commit`] is
+/// called. Disabling staging commits anything still queued.
+pub fn set_staging(enabled: bool) {
+    STAGING.store(enabled, Ordering::SeqCst);
+
+    if !enabled {
+        commit();
+    }
+}
+
+/// Check if staging is enabled
+pub fn is_staging() -> bool {
+    STAGING.load(Ordering::SeqCst)
+}
+
+/// Write a value from outside the application. The type and validators are
+/// checked immediately; if staging is enabled the write is then queued until
+/// the next [`commit`], otherwise it is applied right away.
+pub fn stage<T: AsTuneable>(category: &str, name: &str, value: T::Result) -> Result<(), SetError> {
+    stage_from::<T>(category, name, value, ChangeSource::Code)
+}
+
+/// Like [`stage`], attributing the write to `source` instead of
+/// [`ChangeSource::Code`]
+pub fn stage_from<T: AsTuneable>(
+    category: &str,
+    name: &str,
+    value: T::Result,
+    source: ChangeSource,
+) -> Result<(), SetError> {
+    if !is_staging() {
+        return crate::try_set_from::<T>(category, name, value, source);
+    }
+
+    let value = validated::<T>(category, name, value)?;
+
+    {
+        let tuna = TUNA_STATE.read();
+        let existing = tuna
+            .get(category)
+            .and_then(|group| group.get(name))
+            .ok_or(SetError::NotFound)?;
+
+        if T::from_tuneable(existing).is_none() {
+            return Err(SetError::TypeMismatch);
+        }
+    }
+
+    log::debug!("Staging variable {}/{} = {:?}", category, name, value);
+    STAGED.lock().push((
+        category.to_owned(),
+        name.to_owned(),
+        source,
+        pending::<T>(category, name, value),
+    ));
+
+    Ok(())
+}
+
+/// Apply all staged writes at once. Call this at a frame boundary. Writes are
+/// applied in the order they were staged; any that fail are returned along
+/// with the reason, and passed to the listeners added with
+/// [`add_failure_listener`](crate::add_failure_listener) so whoever staged
+/// them can be told.
+pub fn commit() -> Vec<(String, String, SetError)> {
+    let staged = core::mem::take(&mut *STAGED.lock());
+    if staged.is_empty() {
+        return vec![];
+    }
+
+    let mut tuna = TUNA_STATE.write();
+    let mut changed = vec![];
+    let mut failures = vec![];
+
+    for (category, name, source, write) in staged {
+        match write(&mut tuna, true) {
+            Ok(c) => {
+                source::record(&c, &source);
+                changed.extend(c);
+            }
+            Err(e) => {
+                log::warn!("Failed committing {}/{}: {}", category, name, e);
+                failures.push((category, name, e, source));
+            }
+        }
+    }
+
+    finish_write(tuna, changed);
+    listener::notify_failures(&failures);
+
+    failures
+        .into_iter()
+        .map(|(category, name, e, _)| (category, name, e))
+        .collect()
+}
+
+/// A set of writes that are applied together by [`transaction`]
+#[derive(Default)]
+pub struct Transaction {
+    writes: Vec<(String, String, PendingWrite)>,
+    error: Option<SetError>,
+    source: ChangeSource,
+}
+
+impl Transaction {
+    /// Attribute all writes in the transaction to `source` instead of
+    /// [`ChangeSource::Code`]
+    pub fn source(&mut self, source: ChangeSource) -> &mut Self {
+        self.source = source;
+        self
+    }
+
+    /// Queue a write. Validators run immediately; the value is written when
+    /// the transaction is applied.
+    pub fn set<T: AsTuneable>(
+        &mut self,
+        category: &str,
+        name: &str,
+        value: T::Result,
+    ) -> &mut Self {
+        match validated::<T>(category, name, value) {
+            Ok(value) => self.writes.push((
+                category.to_owned(),
+                name.to_owned(),
+                pending::<T>(category, name, value),
+            )),
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+
+        self
+    }
+}
+
+/// Build a transaction in the closure and apply all of its writes atomically:
+/// either every write succeeds and they become visible together, or nothing
+/// changes. Constraints are checked against the final state, so coupled
+/// variables can be moved together. Reads in the closure see the state from
+/// before the transaction.
+pub fn transaction(build: impl FnOnce(&mut Transaction)) -> Result<(), SetError> {
+    let mut tx = Transaction::default();
+    build(&mut tx);
+
+    if let Some(e) = tx.error {
+        return Err(e);
+    }
+
+    let mut tuna = TUNA_STATE.write();
+    // Only the targets of the writes change, as constraints aren't rebalanced
+    // inside a transaction, so those are all that has to be restored
+    let mut backup: Vec<(String, String, Tuneable)> = vec![];
+    let mut changed = vec![];
+
+    let result = tx
+        .writes
+        .into_iter()
+        .try_for_each(|(category, name, write)| {
+            if !backup.iter().any(|(c, n, _)| *c == category && *n == name) {
+                if let Some(old) = tuna.get(&category).and_then(|group| group.get(&name)) {
+                    backup.push((category.clone(), name.clone(), old.clone()));
+                }
+            }
+
+            changed.extend(write(&mut tuna, false)?);
+            Ok(())
+        })
+        .and_then(|_| constraint::verify(&tuna, &changed).map_err(SetError::Constraint));
+
+    if let Err(e) = result {
+        log::debug!("Rolling back transaction: {}", e);
+        for (category, name, old) in backup {
+            if let Some(slot) = tuna
+                .get_mut(&category)
+                .and_then(|group| group.get_mut(&name))
+            {
+                *slot = old;
+            }
+        }
+        return Err(e);
+    }
+
+    source::record(&changed, &tx.source);
+    finish_write(tuna, changed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit, set_staging, stage, stage_from, transaction};
+    use crate::compat::prelude::*;
+    use crate::{
+        add_constraint, add_failure_listener, clear_constraints, remove_listener, ChangeSource,
+        Constraint, Float32, Int32, SetError,
+    };
+    use serial_test::serial;
+    use std::sync::{Arc, Mutex};
+
+    const TEST_NEAR: Float32 = Float32::new("transaction", "near", 0.1, Some(0.0), None);
+    const TEST_FAR: Float32 = Float32::new("transaction", "far", 100.0, Some(0.0), None);
+    const TEST_INT: Int32 = Int32::new("transaction", "int", 1, None, None);
+
+    #[test]
+    #[serial]
+    fn staging() {
+        TEST_INT.register();
+        TEST_INT.reset();
+        set_staging(true);
+
+        assert!(stage::<Int32>("transaction", "int", 5).is_ok());
+        assert!(stage::<Int32>("transaction", "int", 6).is_ok());
+        assert_eq!(
+            stage::<Float32>("transaction", "int", 5.0),
+            Err(SetError::TypeMismatch)
+        );
+        assert_eq!(TEST_INT.read(), 1);
+
+        assert!(commit().is_empty());
+        assert_eq!(TEST_INT.read(), 6);
+
+        set_staging(false);
+        assert!(stage::<Int32>("transaction", "int", 7).is_ok());
+        assert_eq!(TEST_INT.read(), 7);
+    }
+
+    #[test]
+    #[serial]
+    fn commit_failures() {
+        TEST_NEAR.register();
+        TEST_FAR.register();
+        TEST_NEAR.reset();
+        TEST_FAR.reset();
+        add_constraint(Constraint::ordered(&[
+            ("transaction", "near"),
+            ("transaction", "far"),
+        ]));
+
+        let failed = Arc::new(Mutex::new(vec![]));
+        let sink = failed.clone();
+        let listener = add_failure_listener(move |category, name, error, source| {
+            sink.lock().unwrap().push((
+                format!("{}/{}", category, name),
+                error.clone(),
+                source.clone(),
+            ));
+        });
+
+        // Both are fine on their own, but not together
+        let web = ChangeSource::Web("127.0.0.1:5000".to_owned());
+        set_staging(true);
+        stage_from::<Float32>("transaction", "far", 1.0, ChangeSource::Console).unwrap();
+        stage_from::<Float32>("transaction", "near", 50.0, web.clone()).unwrap();
+
+        let failures = commit();
+        set_staging(false);
+        remove_listener(listener);
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(TEST_FAR.read(), 1.0);
+        assert_eq!(TEST_NEAR.read(), 0.1);
+
+        let failed = failed.lock().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "transaction/near");
+        assert!(matches!(failed[0].1, SetError::Constraint(_)));
+        assert_eq!(failed[0].2, web);
+
+        clear_constraints();
+    }
+
+    #[test]
+    #[serial]
+    fn atomic() {
+        TEST_NEAR.register();
+        TEST_FAR.register();
+        TEST_NEAR.reset();
+        TEST_FAR.reset();
+        add_constraint(Constraint::ordered(&[
+            ("transaction", "near"),
+            ("transaction", "far"),
+        ]));
+
+        // Moving both at once is fine even though near > old far in between
+        transaction(|tx| {
+            tx.set::<Float32>("transaction", "near", 200.0)
+                .set::<Float32>("transaction", "far", 300.0);
+        })
+        .unwrap();
+        assert_eq!(TEST_NEAR.read(), 200.0);
+        assert_eq!(TEST_FAR.read(), 300.0);
+
+        // A failing write rolls back the whole transaction
+        let result = transaction(|tx| {
+            tx.set::<Float32>("transaction", "near", 1.0)
+                .set::<Float32>("transaction", "missing", 1.0);
+        });
+        assert_eq!(result, Err(SetError::NotFound));
+        assert_eq!(TEST_NEAR.read(), 200.0);
+
+        let result = transaction(|tx| {
+            tx.set::<Float32>("transaction", "far", 10.0);
+        });
+        assert!(matches!(result, Err(SetError::Constraint(_))));
+        assert_eq!(TEST_FAR.read(), 300.0);
+
+        clear_constraints();
+    }
+}
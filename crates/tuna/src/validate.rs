@@ -0,0 +1,148 @@
+// Author: Tom Solberg <me@sbg.dev>
+// Copyright © 2026, Tom Solberg, all rights reserved.
+// Created: 19 October 2026
+
+/*!
+Per-variable validators, run on every write regardless of where it comes from.
+*/
+
+use core::any::Any;
+
+use crate::{
+    compat::{prelude::*, Lazy, Map, RwLock},
+    AsTuneable,
+};
+
+type Validator<R> = Box<dyn Fn(R) -> Result<R, String> + Send + Sync>;
+
+/// A type-erased validator, along with the name of the value type it takes
+type Entry = (&'static str, Box<dyn Any + Send + Sync>);
+type ValidatorState = Map<String, Map<String, Vec<Entry>>>;
+
+static VALIDATORS: Lazy<RwLock<ValidatorState>> = Lazy::new(|| RwLock::new(Default::default()));
+
+/// Attach a validator to a variable. The validator receives the incoming value
+/// and can either return a (possibly adjusted) value, or reject the write with
+/// a message. Validators run in the order they were added, before min/max
+/// clamping. Validators of variables rebalanced by a constraint run while the
+/// state is locked, so they must not read or write variables.
+pub fn add_validator<T, F>(category: &str, name: &str, validator: F)
+where
+    T: AsTuneable,
+    F: Fn(T::Result) -> Result<T::Result, String> + Send + Sync + 'static,
+{
+    let boxed: Validator<T::Result> = Box::new(validator);
+    let type_name = core::any::type_name::<T::Result>();
+
+    let registered = crate::TUNA_STATE
+        .read()
+        .get(category)
+        .and_then(|group| group.get(name))
+        .map(|tuneable| T::from_tuneable(tuneable).is_some());
+    if registered == Some(false) {
+        log::warn!(
+            "Validator for {}/{} takes {}, which is not the type of the variable; it will never run",
+            category,
+            name,
+            type_name
+        );
+    }
+
+    VALIDATORS
+        .write()
+        .entry(category.to_owned())
+        .or_default()
+        .entry(name.to_owned())
+        .or_default()
+        .push((type_name, Box::new(boxed)));
+}
+
+/// Remove all validators from a variable
+pub fn clear_validators(category: &str, name: &str) {
+    if let Some(group) = VALIDATORS.write().get_mut(category) {
+        group.remove(name);
+    }
+}
+
+/// Run all validators for the variable on the value
+pub(crate) fn validate<T: AsTuneable>(
+    category: &str,
+    name: &str,
+    value: T::Result,
+) -> Result<T::Result, String> {
+    let validators = VALIDATORS.read();
+
+    let validators = match validators.get(category).and_then(|group| group.get(name)) {
+        Some(v) => v,
+        None => return Ok(value),
+    };
+
+    validators
+        .iter()
+        .filter_map(|(type_name, validator)| {
+            let validator = validator.downcast_ref::<Validator<T::Result>>();
+            if validator.is_none() {
+                log::warn!(
+                    "Skipping validator for {}/{}: it takes {}, but the value is {}",
+                    category,
+                    name,
+                    type_name,
+                    core::any::type_name::<T::Result>()
+                );
+            }
+            validator
+        })
+        .try_fold(value, |value, validator| validator(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compat::prelude::*;
+    use crate::{Float32, Int32, SetError};
+    use serial_test::serial;
+
+    const TEST_POW2: Int32 = Int32::new("validate", "pow2", 4, Some(0), Some(64));
+    const TEST_SNAP: Float32 = Float32::new("validate", "snap", 0.5, Some(0.0), Some(1.0));
+
+    #[test]
+    #[serial]
+    fn reject() {
+        TEST_POW2.register();
+        TEST_POW2.add_validator(|v| {
+            if v.count_ones() == 1 {
+                Ok(v)
+            } else {
+                Err(format!("{} is not a power of two", v))
+            }
+        });
+
+        assert!(crate::try_set::<Int32>("validate", "pow2", 16).is_ok());
+        assert_eq!(TEST_POW2.read(), 16);
+
+        assert_eq!(
+            crate::try_set::<Int32>("validate", "pow2", 15),
+            Err(SetError::Rejected("15 is not a power of two".to_owned()))
+        );
+        assert_eq!(TEST_POW2.read(), 16);
+
+        crate::clear_validators("validate", "pow2");
+        TEST_POW2.write(15);
+        assert_eq!(TEST_POW2.read(), 15);
+    }
+
+    #[test]
+    #[serial]
+    fn adjust() {
+        TEST_SNAP.register();
+        TEST_SNAP.add_validator(|v| Ok((v * 4.0).round() / 4.0));
+
+        TEST_SNAP.write(0.3);
+        assert_eq!(TEST_SNAP.read(), 0.25);
+
+        // clamping still applies after the validator
+        TEST_SNAP.write(1.2);
+        assert_eq!(TEST_SNAP.read(), 1.0);
+
+        crate::clear_validators("validate", "snap");
+    }
+}